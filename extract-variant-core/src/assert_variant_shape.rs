@@ -0,0 +1,38 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Result};
+
+use crate::{field_bindings, fields_stream, VariantShape};
+
+pub fn doit(input: VariantShape) -> Result<TokenStream> {
+    let VariantShape {
+        struct_path,
+        enum_path,
+        variant,
+    } = input;
+    let variant_ident = &variant.ident;
+
+    // Both sides are destructured without `..` and their fields checked against the stated types,
+    // so that a field added, removed, renamed or retyped on either side is a compile error
+    let pattern = fields_stream(&variant.fields);
+    let checks = field_bindings(&variant.fields)
+        .into_iter()
+        .zip(&variant.fields)
+        .map(|(binding, field)| {
+            let ty = &field.ty;
+            quote_spanned! {ty.span()=> let _: #ty = #binding; }
+        })
+        .collect::<TokenStream>();
+    Ok(quote! {
+        const _: () = {
+            #[allow(dead_code, irrefutable_let_patterns)]
+            fn check(value: #enum_path, variant: #struct_path) {
+                let #struct_path #pattern = variant;
+                #checks
+                if let #enum_path::#variant_ident #pattern = value {
+                    #checks
+                }
+            }
+        };
+    })
+}
@@ -0,0 +1,1555 @@
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    ext::IdentExt, parenthesized, parse::ParseStream, parse_quote, parse_quote_spanned,
+    punctuated::Punctuated, token, Attribute, Data, DataEnum, DeriveInput, Error, Expr, Fields,
+    Meta, Path, Result, Token, Variant, VisRestricted, Visibility,
+};
+
+use crate::{
+    generate_variant, impl_variant, parse_expr_tokens, parse_option_name, parse_option_separator,
+    snake_case, Conversion, ExtractVariant, Order, Reserved,
+};
+
+mod assert_macro;
+mod chain;
+mod codes;
+mod collection;
+mod conflicts;
+mod display;
+mod docs;
+mod dynamic;
+mod exploded;
+mod fallible;
+mod fields;
+mod fixtures;
+mod getters;
+mod handler;
+mod indices;
+mod kind;
+mod match_macro;
+mod registry;
+mod round_trip;
+mod seal;
+mod siblings;
+mod sizes;
+mod strict;
+mod tags;
+
+type Options = (Attribute, ExtractVariant);
+type Prefix = (Attribute, Ident);
+type Suffix = (Attribute, Ident);
+type NoImpl = (Attribute,);
+
+/// Everything about the enum that is shared by the code generated for each of its variants.
+struct Context<'a> {
+    input: &'a DeriveInput,
+    options: ExtractVariant,
+    enum_path: Path,
+    /// Whether the enum is defined elsewhere, so that nothing can be implemented on it alone.
+    foreign: bool,
+    /// Whether the enum is `#[non_exhaustive]`, so that other crates may see variants added to it.
+    non_exhaustive: bool,
+    /// The derives of the enum inherited by the structs, narrowed by `inherit_derives`.
+    derives: Vec<Attribute>,
+    /// The visibility of the generated items as seen from the enum: its own, or the one of `pub_in`.
+    vis: Visibility,
+    struct_vis: Visibility,
+    prefix: String,
+    suffix: String,
+}
+
+#[derive(Default)]
+struct Config {
+    options: Option<Options>,
+    prefix: Option<Prefix>,
+    suffix: Option<Suffix>,
+    no_impl: Option<NoImpl>,
+}
+
+pub fn doit(input: DeriveInput) -> Result<TokenStream> {
+    // The settings of `variant_defaults!` are only known to the macro it declares, which calls
+    // back into the expansion with them
+    if input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("extract_variant"))
+        .filter(|attr| matches!(attr.meta, Meta::List(_)))
+        .map(|attr| attr.parse_args::<ExtractVariant>())
+        .collect::<Result<Vec<_>>>()?
+        .iter()
+        .any(|options| options.defaults)
+    {
+        return Ok(quote! { __extract_variant_defaults! { #input } });
+    }
+    expand(&input, None)
+}
+
+/// Returns each variant of the enum `input` with the name of its struct, or `None` if it has no
+/// struct.
+pub fn struct_names(input: &DeriveInput) -> Result<Vec<(Ident, Option<Ident>)>> {
+    let enum_path = Path::from(input.ident.clone());
+    let input = &replace_self(unnest_helper_attrs(input.clone())?, &enum_path)?;
+    let Some(ctx) = Context::new(input, enum_path, false)? else {
+        return Ok(Vec::new());
+    };
+    Ok(ctx
+        .variants()
+        .iter()
+        .map(|variant| {
+            let name = (!ctx.is_skipped(variant)).then(|| {
+                ctx.struct_name(variant)
+                    .unwrap_or_else(|| variant.ident.clone())
+            });
+            (variant.ident.clone(), name)
+        })
+        .collect())
+}
+
+impl<'a> Context<'a> {
+    /// Reads and checks the options of the enum `input`, with `Self` already replaced by
+    /// `enum_path`, or returns `None` if it has no variants, in which case nothing is generated.
+    fn new(input: &'a DeriveInput, enum_path: Path, foreign: bool) -> Result<Option<Self>> {
+        let variants = match &input.data {
+            Data::Enum(DataEnum { variants, .. }) => variants,
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`extract_variant` can only be derived for enums",
+                ))
+            }
+        };
+
+        // If the enum has generic parameters, return a compile-time error
+        if let Some(lt_token) = input.generics.lt_token {
+            return Err(Error::new_spanned(
+                lt_token,
+                "`extract_variant` does not support generic parameters",
+            ));
+        }
+
+        // A field configured out of a tuple would shift the positions of the fields after it
+        let cfg_in_tuple = variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .filter(|field| field.ident.is_none())
+            .flat_map(|field| &field.attrs)
+            .find(|attr| attr.path().is_ident("cfg"));
+        if let Some(attr) = cfg_in_tuple {
+            return Err(Error::new_spanned(
+                attr,
+                "`#[cfg]` is only supported on named fields, as the fields of a tuple are converted by position",
+            ));
+        }
+
+        let mut config = Config::default();
+
+        for attr in &input.attrs {
+            if let Some(ident) = attr.path().get_ident() {
+                match ident.to_string().as_str() {
+                    "extract_variant" => config.fill_options(|| {
+                        let options = match &attr.meta {
+                            Meta::Path(_) => ExtractVariant::default(),
+                            _ => attr.parse_args()?,
+                        };
+                        Ok((attr.clone(), options))
+                    })?,
+                    "prefix" => {
+                        config.fill_prefix(|| attr.parse_args().map(|p| (attr.clone(), p)))?
+                    }
+                    "suffix" => {
+                        config.fill_suffix(|| attr.parse_args().map(|p| (attr.clone(), p)))?
+                    }
+                    "no_impl" => config.fill_no_impl(|| Ok((attr.clone(),)))?,
+                    _ => {}
+                }
+            }
+        }
+
+        let options = config.into_options()?;
+        if options.generate_tests && options.no_impl {
+            return Err(Error::new(
+                Span::call_site(),
+                "`generate_tests` tests the conversions, which `no_impl` disables",
+            ));
+        }
+        // Items declared in a function body are invisible to the modules declared in it
+        if options.local {
+            let modules = [
+                ("hygienic", options.hygienic),
+                ("generate_tests", options.generate_tests),
+                ("report_sizes", options.report_sizes),
+            ];
+            if let Some((name, _)) = modules.iter().find(|(_, enabled)| *enabled) {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "`{}` generates a module, which can't see a `local` enum declared in a function body",
+                        name
+                    ),
+                ));
+            }
+        }
+        if options.accessor_style.is_some() && !options.to_getters {
+            return Err(Error::new(
+                Span::call_site(),
+                "`accessors` names the methods of `to_getters`, which isn't enabled",
+            ));
+        }
+        if options.unchecked_getters && !repr_c(input)? {
+            return Err(Error::new(
+                Span::call_site(),
+                "`unchecked_getters` relies on the layout of a `#[repr(C)]` enum, which this enum doesn't have",
+            ));
+        }
+        if options.kind_strings && !options.kind {
+            return Err(Error::new(
+                Span::call_site(),
+                "`kind_strings` implements traits on the kind enum, which requires `kind`",
+            ));
+        }
+        if options.compare_by_kind && !options.kind {
+            return Err(Error::new(
+                Span::call_site(),
+                "`compare_by_kind` compares the variants through their kinds, which requires `kind`",
+            ));
+        }
+        if (options.kind_index
+            || options.kind_ord
+            || options.kind_key
+            || options.kind_repr.is_some())
+            && !options.kind
+        {
+            return Err(Error::new(
+                Span::call_site(),
+                "`kind_index`, `kind_ord`, `kind_key` and `kind_repr` change the kind enum, which requires `kind`",
+            ));
+        }
+        if options.kind_index && options.kind_repr.is_some() {
+            return Err(Error::new(
+                Span::call_site(),
+                "`kind_index` already represents the kind enum as a `usize`, which `kind_repr` would change",
+            ));
+        }
+        // The tags convert the kind enum from a `u8` as well
+        if options.kind_repr.as_ref().is_some_and(|repr| repr == "u8")
+            && variants
+                .iter()
+                .any(|variant| variant.attrs.iter().any(|attr| attr.path().is_ident("tag")))
+        {
+            return Err(Error::new(
+                Span::call_site(),
+                "the tags already convert the kind enum from a `u8`; give `kind_repr` another type",
+            ));
+        }
+        if options.registry && !(options.kind && options.exploded) {
+            return Err(Error::new(
+                Span::call_site(),
+                "`registry` describes the variants with their kind and builds them from the exploded enum, which requires `kind` and `exploded`",
+            ));
+        }
+        if options.wrong_variant && (options.no_impl || !options.kind) {
+            return Err(Error::new(
+                Span::call_site(),
+                "`wrong_variant` changes the error of the conversions to tell the kind of the enum, which requires `kind` and no `no_impl`",
+            ));
+        }
+        if options.wrong_variant && options.sibling_try_from {
+            return Err(Error::new(
+                Span::call_site(),
+                "`sibling_try_from` converts through `Variant`, which `wrong_variant` doesn't implement",
+            ));
+        }
+        if options.clap_args && options.no_impl {
+            return Err(Error::new(
+                Span::call_site(),
+                "`clap_args` converts the parsed structs into the enum, which `no_impl` disables",
+            ));
+        }
+        if options.seal && (options.no_impl || options.wrong_variant) {
+            return Err(Error::new(
+                Span::call_site(),
+                "`seal` builds on the `Variant` impls, which `no_impl` and `wrong_variant` leave out",
+            ));
+        }
+        if !options.chain.is_empty() && (options.no_impl || options.wrong_variant) {
+            return Err(Error::new(
+                Span::call_site(),
+                "`chain` goes through the conversions of the enum, which `no_impl` leaves out and `wrong_variant` changes the error of",
+            ));
+        }
+        if options.match_macro && options.no_impl {
+            return Err(Error::new(
+                Span::call_site(),
+                "`match_macro` unwraps the variants through the conversions, which `no_impl` disables",
+            ));
+        }
+        if options.sibling_try_from && options.no_impl {
+            return Err(Error::new(
+                Span::call_site(),
+                "`sibling_try_from` converts through the conversions, which `no_impl` disables",
+            ));
+        }
+        let non_exhaustive = input
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("non_exhaustive"));
+        if options.defaults && foreign {
+            return Err(Error::new(
+                Span::call_site(),
+                "`defaults` is only supported by `#[derive(extract_variant)]`",
+            ));
+        }
+        // An enum declared elsewhere can't be given methods, nor traits of other crates
+        if foreign {
+            let on_enum = [
+                ("kind", options.kind),
+                ("to_getters", options.to_getters),
+                ("as_getters", options.as_getters),
+                ("unchecked_getters", options.unchecked_getters),
+                ("display", options.display),
+                ("handler", options.handler),
+                ("fixtures", options.fixtures.is_some()),
+                ("indices", options.indices.is_some()),
+            ];
+            let name = on_enum
+                .iter()
+                .find(|(_, enabled)| *enabled)
+                .map(|(name, _)| format!("`{}`", name));
+            if let Some(name) = name {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "{} implements items on the enum, which can only be done where it's declared",
+                        name
+                    ),
+                ));
+            }
+            let code = variants.iter().find_map(|variant| {
+                variant
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path().is_ident("code"))
+            });
+            if let Some(attr) = code {
+                return Err(Error::new_spanned(
+                    attr,
+                    "`#[code(...)]` implements a `code` method on the enum, which can only be done where it's declared",
+                ));
+            }
+        }
+        // Code generated outside of the crate of a non-exhaustive enum can't list all of its variants
+        if non_exhaustive && foreign {
+            let exhaustive = [
+                ("exploded", options.exploded),
+                ("match_macro", options.match_macro),
+                ("assert_macro", options.assert_macro.is_some()),
+            ];
+            if let Some((name, _)) = exhaustive.iter().find(|(_, enabled)| *enabled) {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "`{}` needs every variant of the enum, which `..` or `#[non_exhaustive]` leaves unknown",
+                        name
+                    ),
+                ));
+            }
+        }
+        for attr in variants
+            .iter()
+            .flat_map(|variant| &variant.attrs)
+            .filter(|attr| attr.path().is_ident("exclude"))
+        {
+            if let Meta::List(_) = attr.meta {
+                let which: Ident = attr.parse_args()?;
+                if !["struct_only", "all", "kind_only", "keep_impls"]
+                    .iter()
+                    .any(|expected| which == expected)
+                {
+                    return Err(Error::new_spanned(
+                        which,
+                        "expected `struct_only`, `all`, `kind_only` or `keep_impls`",
+                    ));
+                }
+            }
+        }
+        // An enum without variants has nothing to extract, and no value to build anything from
+        if variants.is_empty() {
+            return Ok(None);
+        }
+
+        let prefix = options
+            .prefix
+            .as_ref()
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+        let suffix = options
+            .suffix
+            .as_ref()
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+
+        // The enum's derives are inherited by every generated struct, so only look them up once
+        let derives = inherited_derives(input, options.inherit_derives.as_deref())?;
+
+        let vis = match &options.pub_in {
+            Some(path) => {
+                // Visibilities can only name ancestor modules, which the compiler checks once the path
+                // is resolved; it can only be relative to where the enum is, or absolute
+                let first = path.segments.first().map(|segment| &segment.ident);
+                if path.leading_colon.is_some()
+                    || !first.is_some_and(|first| {
+                        first == "crate" || first == "self" || first == "super"
+                    })
+                {
+                    return Err(Error::new_spanned(
+                        path,
+                        "`pub_in` takes the path of an ancestor module, starting with `crate`, `self` or `super`",
+                    ));
+                }
+                parse_quote!(pub(in #path))
+            }
+            None => input.vis.clone(),
+        };
+        // In hygienic mode the structs are moved one module deeper, so their visibility has to
+        // reach one module further to stay visible from where the enum is
+        let struct_vis = if options.hygienic {
+            nested_visibility(&vis)
+        } else {
+            vis.clone()
+        };
+
+        let ctx = Context {
+            input,
+            options,
+            enum_path,
+            foreign,
+            non_exhaustive,
+            derives,
+            vis,
+            struct_vis,
+            prefix,
+            suffix,
+        };
+
+        if ctx.options.reserved == Some(Reserved::Error) {
+            for variant in variants.iter().filter(|variant| !ctx.is_skipped(variant)) {
+                let struct_ident = ctx
+                    .struct_name(variant)
+                    .unwrap_or_else(|| variant.ident.clone());
+                if PRELUDE_NAMES.contains(&struct_ident.to_string().as_str()) {
+                    return Err(Error::new(
+                        variant.ident.span(),
+                        format!(
+                            "the struct `{0}` would shadow `{0}` from the prelude; rename it, or use `reserved(suffix)` or `reserved(shadow)`",
+                            struct_ident
+                        ),
+                    ));
+                }
+            }
+        }
+
+        if ctx.options.strict {
+            strict::check_strict(&ctx, input, variants)?;
+        }
+        conflicts::check_conflicts(&ctx, variants)?;
+
+        Ok(Some(ctx))
+    }
+
+    /// Returns the variants of the enum.
+    fn variants(&self) -> &'a Punctuated<Variant, Token![,]> {
+        match &self.input.data {
+            Data::Enum(DataEnum { variants, .. }) => variants,
+            _ => unreachable!("the input is checked to be an enum"),
+        }
+    }
+}
+
+/// Generates the structs of the variants of `input`, converting from and into the enum.
+///
+/// The enum is usually `input` itself, but `extract_variants_of!` restates the shape of an enum
+/// from elsewhere, which is then only reachable through its path, given as `foreign_path`.
+pub(crate) fn expand(input: &DeriveInput, foreign_path: Option<Path>) -> Result<TokenStream> {
+    let foreign = foreign_path.is_some();
+    let enum_path = foreign_path.unwrap_or_else(|| Path::from(input.ident.clone()));
+    let input = &replace_self(unnest_helper_attrs(input.clone())?, &enum_path)?;
+    let Some(ctx) = Context::new(input, enum_path, foreign)? else {
+        return Ok(TokenStream::new());
+    };
+    let variants = ctx.variants();
+
+    // The lists of variants follow `order`, unlike what mirrors the enum, such as the kind enum
+    let mut ordered: Vec<&Variant> = variants.iter().collect();
+    if ctx.options.order == Some(Order::Alphabetical) {
+        ordered.sort_by_key(|variant| variant.ident.to_string());
+    }
+
+    // The names of the generated types, to re-export them in hygienic mode
+    let mut type_idents = Vec::with_capacity(variants.len() + 1);
+    // The structs less visible than the enum, which are re-exported on their own
+    let mut narrowed = Vec::new();
+    // The structs declared by hand, which are already where the enum is
+    let mut declared = Vec::new();
+    let mut output = ordered
+        .iter()
+        .filter(|variant| !ctx.is_skipped(variant))
+        .map(|variant| {
+            let struct_name = ctx.struct_name(variant);
+            let struct_ident = struct_name.clone().unwrap_or_else(|| variant.ident.clone());
+            if ctx.keeps_impls(variant) {
+                declared.push(struct_ident.clone());
+            } else if let Some(vis) = ctx.narrowed_visibility(variant)? {
+                narrowed.push((struct_ident.clone(), vis));
+            }
+            type_idents.push(struct_ident);
+            generate_code(&ctx, variant, struct_name)
+        })
+        // Collect all of the generated structs and trait implementations into a single TokenStream
+        .collect::<Result<TokenStream>>()?;
+
+    // Only the structs have been collected so far. The list would leak the ones less visible
+    // than the enum, which `pub_in` may make all of them
+    let restricted = ctx.options.pub_in.is_some() && visibility_rank(&input.vis) > 0;
+    if !ctx.foreign && narrowed.is_empty() && !restricted {
+        let enum_path = &ctx.enum_path;
+        output.extend(quote! {
+            impl ::variant_traits::Variants for #enum_path {
+                type List = (#(#type_idents,)*);
+            }
+        });
+    }
+
+    // The other generated types are added after the structs, which the sibling conversions are
+    // between
+    let struct_count = type_idents.len();
+    if ctx.options.seal {
+        let (trait_ident, seal) = seal::generate_seal(&ctx, variants)?;
+        type_idents.push(trait_ident);
+        output.extend(seal);
+    }
+    if !ctx.options.chain.is_empty() {
+        output.extend(chain::impl_chains(&ctx, variants)?);
+    }
+    if ctx.options.sibling_try_from {
+        output.extend(siblings::impl_sibling_try_froms(
+            &ctx,
+            &type_idents[..struct_count],
+        ));
+    }
+    if ctx.options.exploded {
+        let (exploded_ident, exploded) =
+            exploded::generate_exploded(&ctx, ordered.iter().copied())?;
+        type_idents.push(exploded_ident);
+        output.extend(exploded);
+    }
+    if ctx.options.kind {
+        let (kind_ident, kind) = kind::generate_kind(&ctx, variants)?;
+        type_idents.push(kind_ident);
+        output.extend(kind);
+        if ctx.options.kind_strings {
+            output.extend(kind::impl_kind_strings(&ctx, variants)?);
+        }
+    }
+    if ctx.options.registry {
+        let (registry_ident, registry) = registry::generate_registry(&ctx, ordered.iter().copied());
+        type_idents.push(registry_ident);
+        output.extend(registry);
+    }
+    if ctx.options.handler {
+        let (handler_ident, handler) = handler::generate_handler(&ctx, ordered.iter().copied())?;
+        type_idents.push(handler_ident);
+        output.extend(handler);
+    }
+    if ctx.options.display {
+        output.extend(display::impl_display(&ctx, variants)?);
+    }
+    if ctx.options.as_getters {
+        output.extend(getters::impl_as_getters(&ctx, variants)?);
+    }
+    // An enum from another crate can't get conversions into another foreign type
+    if let Some(value_path) = ctx.options.dynamic.as_ref().filter(|_| !ctx.foreign) {
+        output.extend(dynamic::impl_dynamic(value_path, &ctx.enum_path)?);
+    }
+    if let Some(which) = ctx.options.indices {
+        output.extend(indices::generate_indices(&ctx, variants, which));
+    }
+    output.extend(codes::generate_codes(&ctx, variants)?);
+    output.extend(tags::generate_tags(&ctx, variants)?);
+    if ctx.options.fixtures.is_some() {
+        output.extend(fixtures::generate_fixtures(&ctx, variants)?);
+    }
+    if ctx.options.generate_tests {
+        output.extend(round_trip::generate_tests(&ctx, variants)?);
+    }
+    if ctx.options.report_sizes {
+        output.extend(sizes::generate_size_report(&ctx, variants));
+    }
+
+    let mut output = if ctx.options.hygienic {
+        let vis = &ctx.vis;
+        let module = Ident::new(
+            &format!("__extract_variant_{}", input.ident),
+            input.ident.span(),
+        );
+        let type_idents = type_idents.iter().filter(|ident| {
+            !narrowed.iter().any(|(narrowed, _)| narrowed == *ident) && !declared.contains(ident)
+        });
+        let narrowed = narrowed
+            .iter()
+            .map(|(ident, vis)| quote! { #vis use #module::#ident; });
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            mod #module {
+                use super::*;
+                #output
+            }
+            #vis use #module::{#(#type_idents),*};
+            #(#narrowed)*
+        }
+    } else {
+        output
+    };
+    // Macros are scoped by where they're defined rather than by paths, so it can't be re-exported
+    // from the hidden module
+    if ctx.options.match_macro {
+        output.extend(match_macro::generate_match_macro(&ctx, variants));
+    }
+    if let Some(exported) = &ctx.options.assert_macro {
+        output.extend(assert_macro::generate_assert_macro(
+            &ctx,
+            exported.as_ref(),
+        )?);
+    }
+    let mut lints = ctx.options.allow.clone();
+    if ctx.options.allow_unused {
+        lints.push(parse_quote!(dead_code));
+    }
+    if !lints.is_empty() {
+        output = allow_lints(output, &lints)?;
+    }
+    Ok(output)
+}
+
+/// Returns whether the enum `input` is `#[repr(C)]`, possibly along with the type of its tag, e.g.
+/// `#[repr(C, u8)]`.
+fn repr_c(input: &DeriveInput) -> Result<bool> {
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        let hints = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        if hints.iter().any(|hint| hint.path().is_ident("C")) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Returns the `#[repr(...)]` attributes of the enum `input` as they apply to a field-less enum with
+/// the same discriminants: `#[repr(C, u8)]` is that of a `#[repr(u8)]` one, as a field-less enum
+/// can't have both.
+fn fieldless_reprs(input: &DeriveInput) -> Result<Vec<Attribute>> {
+    let mut reprs = Vec::new();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        let mut hints = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        let sized = hints
+            .iter()
+            .any(|hint| !hint.path().is_ident("C") && !hint.path().is_ident("align"));
+        if sized {
+            hints = hints
+                .into_iter()
+                .filter(|hint| !hint.path().is_ident("C"))
+                .collect();
+        }
+        reprs.push(parse_quote!(#[repr(#hints)]));
+    }
+    Ok(reprs)
+}
+
+/// Puts `#[allow(...)]` with the given lints on each of the generated items, which extends to
+/// whatever is nested in them.
+fn allow_lints(output: TokenStream, lints: &[Path]) -> Result<TokenStream> {
+    add_item_attrs(output, &[parse_quote!(#[allow(#(#lints),*)])])
+}
+
+/// Puts the given attributes on each of the generated items.
+///
+/// The items are told apart by their tokens rather than parsed, which would need syn's `full`
+/// feature: an item ends with a `;`, or with its braces unless it's a `const`, `static`, `type` or
+/// `use`, whose value may be braced.
+pub(crate) fn add_item_attrs(output: TokenStream, new_attrs: &[Attribute]) -> Result<TokenStream> {
+    let mut result = TokenStream::new();
+    let mut tokens = output.into_iter().peekable();
+    while tokens.peek().is_some() {
+        // The attributes the item already has come first, as `#` and `[...]`
+        let mut attrs = TokenStream::new();
+        while matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+            attrs.extend(tokens.next());
+            attrs.extend(tokens.next());
+        }
+        let mut item = TokenStream::new();
+        // Whether the item ends with a `;` only, known once its keyword is seen
+        let mut until_semi = None;
+        while let Some(token) = tokens.next() {
+            let ends = match &token {
+                TokenTree::Punct(punct) if punct.as_char() == ';' => true,
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                    until_semi != Some(true)
+                }
+                TokenTree::Ident(ident) if until_semi.is_none() => {
+                    if ident != "pub" && ident != "unsafe" {
+                        until_semi = Some(
+                            ident == "const" && !is_const_fn(tokens.peek())
+                                || ident == "static"
+                                || ident == "type"
+                                || ident == "use",
+                        );
+                    }
+                    false
+                }
+                _ => false,
+            };
+            item.extend([token]);
+            if ends {
+                break;
+            }
+        }
+        result.extend(quote! { #attrs #(#new_attrs)* #item });
+    }
+    Ok(result)
+}
+
+/// Whether the token after `const` makes it a `const fn` rather than a constant.
+fn is_const_fn(next: Option<&TokenTree>) -> bool {
+    matches!(next, Some(TokenTree::Ident(ident)) if ident == "fn" || ident == "unsafe")
+}
+
+fn generate_code(
+    ctx: &Context,
+    variant: &Variant,
+    struct_name: Option<Ident>,
+) -> Result<TokenStream> {
+    // Apply the field-level attributes to a copy of the variant, which the struct is generated from
+    let (fields, maps) = fields::struct_fields(ctx, variant)?;
+    let mapped = Variant {
+        fields: fields.clone(),
+        ..variant.clone()
+    };
+
+    // Generate a struct for the current variant
+    let mut item_struct = generate_variant(ctx.input, &mapped, struct_name);
+    item_struct.vis = match ctx.narrowed_visibility(variant)? {
+        Some(vis) if ctx.options.hygienic => nested_visibility(&vis),
+        Some(vis) => vis,
+        None => ctx.struct_vis.clone(),
+    };
+
+    // Sort the variant's attributes in a single pass
+    let mut variant_derives = Vec::new();
+    let mut impl_attrs = Vec::new();
+    let mut docs = Vec::new();
+    let mut doc_inherit = None;
+    for attr in &variant.attrs {
+        let Some(ident) = attr.path().get_ident() else {
+            continue;
+        };
+        match ident.to_string().as_str() {
+            "variant_derive" => variant_derives
+                .extend(attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?),
+            "impl_attrs" => impl_attrs.extend(attr.parse_args_with(parse_variant_attrs)?),
+            "doc" => docs.push(docs::rewrite_doc_links(ctx, attr)),
+            "doc_inherit" | "no_doc_inherit" => {
+                if doc_inherit.is_some() {
+                    return Err(Error::new_spanned(
+                        attr.pound_token,
+                        "duplicate #[doc_inherit] or #[no_doc_inherit] attribute",
+                    ));
+                }
+                doc_inherit = Some(ident == "doc_inherit");
+            }
+            _ => {}
+        }
+    }
+
+    // If the variant has a "variant_attrs" attribute, add the attributes meant for the struct
+    item_struct.attrs.extend(ctx.variant_attrs(variant)?.owned);
+    // The payloads of a `#[repr(C)]` enum are `#[repr(C)]` structs, which the struct then matches
+    if repr_c(ctx.input)?
+        && !item_struct
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("repr"))
+    {
+        item_struct.attrs.push(parse_quote!(#[repr(C)]));
+    }
+    // Shortcut 1, leaving `Debug` to `qualified_debug`
+    if ctx.options.qualified_debug {
+        item_struct.attrs.extend(
+            ctx.derives
+                .iter()
+                .filter_map(|attr| exploded::without_derive(attr, "Debug")),
+        );
+    } else {
+        item_struct.attrs.extend(ctx.derives.iter().cloned());
+    }
+    // Unit structs can always derive the traits given for them
+    if matches!(fields, Fields::Unit) {
+        variant_derives.extend(ctx.options.unit_derives.iter().cloned());
+    }
+    // Structs with a single unnamed field are newtypes, which can deref to it and so on
+    if matches!(&fields, Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1) {
+        variant_derives.extend(ctx.options.newtype_derives.iter().cloned());
+    }
+    // The fields keep their `#[arg(...)]` attributes, which the derive reads
+    if ctx.options.clap_args {
+        if matches!(fields, Fields::Unnamed(_)) {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                "`clap_args` requires variants with named fields or no fields; exclude this one",
+            ));
+        }
+        variant_derives.push(parse_quote!(::clap::Args));
+    }
+    // The variant's own derives, minus the ones it already inherits from the enum and duplicates
+    let mut derived = Vec::new();
+    for attr in &ctx.derives {
+        let paths = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+        derived.extend(paths.iter().map(|path| quote!(#path).to_string()));
+    }
+    variant_derives.retain(|path| {
+        let path = quote!(#path).to_string();
+        let new = !derived.contains(&path);
+        derived.push(path);
+        new
+    });
+    if !variant_derives.is_empty() {
+        item_struct
+            .attrs
+            .push(parse_quote!(#[derive(#(#variant_derives),*)]));
+    }
+    // Shortcut 2
+    if doc_inherit.unwrap_or(!ctx.options.no_doc_inherit) {
+        item_struct.attrs.extend(docs);
+    }
+    // Make the struct show up when searching the docs for the variant
+    let alias = format!("{}::{}", ctx.input.ident, variant.ident);
+    item_struct.attrs.push(parse_quote!(#[doc(alias = #alias)]));
+    if item_struct.ident != variant.ident {
+        let alias = variant.ident.to_string();
+        item_struct.attrs.push(parse_quote!(#[doc(alias = #alias)]));
+    }
+
+    // With `#[exclude(keep_impls)]`, the struct is declared by hand and only gets the impls
+    let mut output = if ctx.keeps_impls(variant) {
+        TokenStream::new()
+    } else {
+        quote! { #item_struct }
+    };
+    // Unit variants have a single value, which the conversions can't give in const contexts
+    if matches!(variant.fields, Fields::Unit) {
+        let struct_ident = &item_struct.ident;
+        let vis = &item_struct.vis;
+        let enum_path = &ctx.enum_path;
+        let variant_ident = &variant.ident;
+        output.extend(quote! {
+            impl #struct_ident {
+                /// The variant in the enum, for const contexts where the conversions aren't
+                /// available.
+                #vis const VALUE: #enum_path = #enum_path::#variant_ident;
+            }
+        });
+    }
+    // The conversions, which get the attributes of `#[impl_attrs(...)]`
+    let mut impls = TokenStream::new();
+    if let Some(fallible) = fallible::fallible_from(ctx, variant)? {
+        impls.extend(fallible::impl_fallible_variant(
+            ctx,
+            &fallible,
+            &item_struct.ident,
+            &variant.ident,
+            &Conversion::new(&variant.fields, &fields, &maps),
+        ));
+    } else if !ctx.options.no_impl {
+        // If the "no_impl" flag is not set, generate trait implementations for the struct
+        let variant_name = Some(&variant.ident);
+        impls.extend(impl_variant(
+            &item_struct.ident,
+            &Conversion::new(&variant.fields, &fields, &maps),
+            &ctx.enum_path,
+            variant_name,
+            ctx.options.wrong_variant,
+        ));
+    }
+    if ctx.options.qualified_debug {
+        output.extend(display::impl_qualified_debug(
+            ctx,
+            variant,
+            &item_struct.ident,
+            &fields,
+        ));
+    }
+    if ctx.options.unchecked_getters && !ctx.keeps_impls(variant) {
+        output.extend(getters::impl_unchecked_getter(
+            ctx,
+            variant,
+            &item_struct.ident,
+            &item_struct.attrs,
+            &fields,
+            &maps,
+        )?);
+    }
+    if ctx.options.to_getters {
+        output.extend(getters::impl_to_getter(
+            ctx,
+            variant,
+            &item_struct.ident,
+            &fields,
+            &maps,
+        )?);
+    }
+    if variant
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("into_iterator"))
+    {
+        impls.extend(collection::impl_collection(
+            variant,
+            &item_struct.ident,
+            &fields,
+        )?);
+    }
+    if let Some(value_path) = &ctx.options.dynamic {
+        impls.extend(dynamic::impl_dynamic(
+            value_path,
+            &Path::from(item_struct.ident.clone()),
+        )?);
+    }
+    if ctx.options.readonly {
+        output.extend(getters::impl_readonly_accessors(
+            ctx,
+            &item_struct.ident,
+            &fields,
+        ));
+    }
+    if ctx.options.from_arc {
+        impls.extend(getters::impl_try_from_arc(
+            ctx,
+            variant,
+            &item_struct.ident,
+            &fields,
+            &maps,
+        ));
+    }
+    if impl_attrs.is_empty() {
+        output.extend(impls);
+    } else {
+        output.extend(add_item_attrs(impls, &impl_attrs)?);
+    }
+    Ok(output)
+}
+
+/// Returns each variant along with the value given to it by a `#[name(...)]` attribute, or `None` if
+/// no variant has one. Once a variant has one, every variant must.
+fn variant_values<'v>(
+    variants: impl IntoIterator<Item = &'v Variant>,
+    name: &str,
+) -> Result<Option<Vec<(&'v Variant, Expr)>>> {
+    let mut values = Vec::new();
+    let mut missing = None;
+    for variant in variants {
+        let mut value = None;
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(name))
+        {
+            if value.is_some() {
+                return Err(Error::new_spanned(
+                    attr.pound_token,
+                    format!("duplicate #[{}] attribute", name),
+                ));
+            }
+            value = Some(attr.parse_args_with(parse_expr_tokens)?);
+        }
+        match value {
+            Some(value) => values.push((variant, value)),
+            None => {
+                missing.get_or_insert(variant);
+            }
+        }
+    }
+    match missing {
+        Some(_) if values.is_empty() => Ok(None),
+        Some(variant) => Err(Error::new_spanned(
+            &variant.ident,
+            format!("missing #[{}(...)], since other variants have one", name),
+        )),
+        None => Ok(Some(values)),
+    }
+}
+
+/// Parses the content of `#[impl_attrs(...)]`: attributes either written out as `#[...]`, or
+/// bare like `derive(Debug)` or `doc = "..."`, in which case they are separated by commas.
+fn parse_variant_attrs(input: ParseStream) -> Result<Vec<Attribute>> {
+    let mut attrs = Vec::new();
+    while !input.is_empty() {
+        attrs.extend(parse_variant_attr(input)?);
+    }
+    Ok(attrs)
+}
+
+/// Parses the next entry of [parse_variant_attrs]: consecutive `#[...]` attributes, or a bare one.
+fn parse_variant_attr(input: ParseStream) -> Result<Vec<Attribute>> {
+    if input.peek(Token![#]) {
+        let attrs = input.call(Attribute::parse_outer)?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(attrs)
+    } else {
+        let meta: Meta = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        Ok(vec![parse_quote!(#[#meta])])
+    }
+}
+
+/// The attributes of `#[variant_attrs(...)]`, by the generated item they go on.
+#[derive(Default)]
+struct VariantAttrs {
+    /// The attributes of the struct.
+    owned: Vec<Attribute>,
+    /// The attributes of the variant of the kind enum.
+    kind: Vec<Attribute>,
+    /// The attributes of the variant of the exploded enum.
+    exploded: Vec<Attribute>,
+}
+
+/// Parses the content of `#[variant_attrs(...)]`, written like [parse_variant_attrs], where an
+/// entry can be preceded by `owned:`, `kind:` or `exploded:` to choose the item it goes on, the
+/// struct by default.
+fn parse_targeted_attrs(input: ParseStream) -> Result<VariantAttrs> {
+    let mut attrs = VariantAttrs::default();
+    while !input.is_empty() {
+        // `Token![:]` also peeks the first colon of a path, e.g. `rustfmt::skip`
+        let target =
+            if input.peek(Ident::peek_any) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+                let target = input.call(Ident::parse_any)?;
+                input.parse::<Token![:]>()?;
+                Some(target)
+            } else {
+                None
+            };
+        let entry = parse_variant_attr(input)?;
+        let Some(target) = target else {
+            attrs.owned.extend(entry);
+            continue;
+        };
+        match target.to_string().as_str() {
+            "owned" => attrs.owned.extend(entry),
+            "kind" => attrs.kind.extend(entry),
+            "exploded" => attrs.exploded.extend(entry),
+            "ref" | "mut" | "builder" => {
+                return Err(Error::new(
+                    target.span(),
+                    format!("no `{}` companion is generated for the variants", target),
+                ))
+            }
+            _ => {
+                return Err(Error::new(
+                    target.span(),
+                    "expected `owned`, `kind` or `exploded`",
+                ))
+            }
+        }
+    }
+    Ok(attrs)
+}
+
+/// The types and traits of the prelude, along with the variants of `Option` and `Result`, which a
+/// struct of the same name would shadow in the whole module.
+const PRELUDE_NAMES: &[&str] = &[
+    "AsMut",
+    "AsRef",
+    "Box",
+    "Clone",
+    "Copy",
+    "Default",
+    "DoubleEndedIterator",
+    "Drop",
+    "Eq",
+    "Err",
+    "ExactSizeIterator",
+    "Extend",
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "From",
+    "FromIterator",
+    "Into",
+    "IntoIterator",
+    "Iterator",
+    "None",
+    "Ok",
+    "Option",
+    "Ord",
+    "PartialEq",
+    "PartialOrd",
+    "Result",
+    "Send",
+    "Sized",
+    "Some",
+    "String",
+    "Sync",
+    "ToOwned",
+    "ToString",
+    "TryFrom",
+    "TryInto",
+    "Unpin",
+    "Vec",
+];
+
+/// The helper attributes of variants and fields that can also be written inside an
+/// `#[extract_variant(...)]` attribute, out of the way of other derives using the same names.
+const NESTED_HELPERS: &[&str] = &[
+    "variant_attrs",
+    "variant_derive",
+    "impl_attrs",
+    "fallible_from",
+    "display_skip",
+    "display_fmt",
+    "exclude",
+    "boxed",
+    "unboxed",
+    "map_type",
+    "tuple",
+    "into_iterator",
+    "code",
+    "tag",
+    "sample",
+    "kind_name",
+    "accessor_name",
+    "doc_inherit",
+    "no_doc_inherit",
+    "vis",
+];
+
+/// Replaces `Self` in the types of the fields with the path to the enum, which it stands for in
+/// the enum but would stand for the struct once the fields are moved into it.
+fn replace_self(mut input: DeriveInput, enum_path: &Path) -> Result<DeriveInput> {
+    if let Data::Enum(data) = &mut input.data {
+        for field in data
+            .variants
+            .iter_mut()
+            .flat_map(|variant| &mut variant.fields)
+        {
+            let ty = &field.ty;
+            field.ty = syn::parse2(replace_self_tokens(quote! { #ty }, enum_path))?;
+        }
+    }
+    Ok(input)
+}
+
+/// Replaces each `Self` in `tokens`, nested ones included, with `enum_path`.
+pub(crate) fn replace_self_tokens(tokens: TokenStream, enum_path: &Path) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) if ident == "Self" => {
+                let mut enum_path = enum_path.clone();
+                // Keep the span of `Self` so that errors about the type still point at the field
+                for segment in &mut enum_path.segments {
+                    segment.ident.set_span(ident.span());
+                }
+                quote! { #enum_path }
+            }
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(
+                    group.delimiter(),
+                    replace_self_tokens(group.stream(), enum_path),
+                );
+                replaced.set_span(group.span());
+                quote! { #replaced }
+            }
+            token => quote! { #token },
+        })
+        .collect()
+}
+
+/// Replaces the `#[extract_variant(...)]` attributes of the variants and fields by the helper
+/// attributes they hold, which take precedence over the same helpers written on their own.
+fn unnest_helper_attrs(mut input: DeriveInput) -> Result<DeriveInput> {
+    if let Data::Enum(data) = &mut input.data {
+        for variant in &mut data.variants {
+            unnest_attrs(&mut variant.attrs)?;
+            for field in &mut variant.fields {
+                unnest_attrs(&mut field.attrs)?;
+            }
+        }
+    }
+    Ok(input)
+}
+
+fn unnest_attrs(attrs: &mut Vec<Attribute>) -> Result<()> {
+    let mut nested = Vec::new();
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("extract_variant"))
+    {
+        nested.extend(attr.parse_args_with(parse_nested_helpers)?);
+    }
+    if nested.is_empty() {
+        return Ok(());
+    }
+    // `doc_inherit` and `no_doc_inherit` are two values of the same setting
+    let setting = |attr: &Attribute| {
+        let name = attr.path().get_ident().map(Ident::to_string);
+        match name.as_deref() {
+            Some("no_doc_inherit") => Some("doc_inherit".to_owned()),
+            _ => name,
+        }
+    };
+    let overridden: Vec<_> = nested.iter().map(setting).collect();
+    attrs.retain(|attr| {
+        !attr.path().is_ident("extract_variant") && !overridden.contains(&setting(attr))
+    });
+    attrs.extend(nested);
+    Ok(())
+}
+
+/// Parses the content of an `#[extract_variant(...)]` attribute on a variant or a field: helper
+/// attributes written without their `#[...]`, like `exclude` or `map_type(0: u8 => u16)`.
+fn parse_nested_helpers(input: ParseStream) -> Result<Vec<Attribute>> {
+    let mut attrs = Vec::new();
+    while !input.is_empty() {
+        let ident = parse_option_name(input)?;
+        if !NESTED_HELPERS.contains(&ident.to_string().as_str()) {
+            return Err(Error::new_spanned(
+                &ident,
+                format!("invalid parameter name `{}`", ident),
+            ));
+        }
+        if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let args: TokenStream = content.parse()?;
+            attrs.push(parse_quote_spanned!(ident.span()=> #[#ident(#args)]));
+        } else {
+            attrs.push(parse_quote_spanned!(ident.span()=> #[#ident]));
+        }
+        parse_option_separator(input)?;
+    }
+    Ok(attrs)
+}
+
+/// Returns the `derive` attributes of the enum that the structs inherit: all of them, or only the
+/// traits listed in `inherit_derives`, each of which has to be derived by the enum.
+fn inherited_derives(input: &DeriveInput, filter: Option<&[Path]>) -> Result<Vec<Attribute>> {
+    let derives = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"));
+    let Some(filter) = filter else {
+        return Ok(derives.cloned().collect());
+    };
+    let names: Vec<String> = filter
+        .iter()
+        .map(|path| quote!(#path).to_string())
+        .collect();
+    let mut found = Vec::new();
+    let mut inherited = Vec::new();
+    for attr in derives {
+        let paths: Punctuated<Path, Token![,]> = attr
+            .parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?
+            .into_iter()
+            .filter(|path| {
+                let name = quote!(#path).to_string();
+                let kept = names.contains(&name);
+                found.push(name);
+                kept
+            })
+            .collect();
+        if !paths.is_empty() {
+            let mut attr = attr.clone();
+            attr.meta = parse_quote! { derive(#paths) };
+            inherited.push(attr);
+        }
+    }
+    if let Some((path, _)) = filter
+        .iter()
+        .zip(&names)
+        .find(|(_, name)| !found.contains(name))
+    {
+        return Err(Error::new_spanned(
+            path,
+            "`inherit_derives` can only list traits derived by the enum",
+        ));
+    }
+    Ok(inherited)
+}
+
+/// Returns the visibility that, applied to an item inside a child module, makes the item visible
+/// to exactly the same modules as `vis` would outside of it.
+fn nested_visibility(vis: &Visibility) -> Visibility {
+    match vis {
+        Visibility::Inherited => parse_quote!(pub(super)),
+        Visibility::Restricted(VisRestricted { path, .. }) => {
+            let mut segments = path.segments.iter();
+            match segments.next() {
+                // `pub(self)` and `pub(in self::..)` refer to the enum's module, which is now `super`
+                Some(first) if first.ident == "self" => {
+                    let rest = segments.map(|segment| &segment.ident);
+                    parse_quote!(pub(in super #(::#rest)*))
+                }
+                // `pub(super)` and `pub(in super::..)` need one more `super`
+                Some(first) if first.ident == "super" => parse_quote!(pub(in super::#path)),
+                // `pub(crate)` and `pub(in crate::..)` are absolute
+                _ => vis.clone(),
+            }
+        }
+        _ => vis.clone(),
+    }
+}
+
+/// Orders visibilities from the narrowest to the widest, as far as it can be told from their paths:
+/// restricted ones other than `pub(crate)` can't be compared with each other.
+fn visibility_rank(vis: &Visibility) -> u8 {
+    match vis {
+        Visibility::Inherited => 0,
+        Visibility::Restricted(VisRestricted { path, .. }) if path.is_ident("crate") => 2,
+        Visibility::Restricted(_) => 1,
+        Visibility::Public(_) => 3,
+    }
+}
+
+impl Context<'_> {
+    /// Returns the visibility of the struct of `variant` under `vis_from_fields`, as seen from the
+    /// enum, if it's narrower than the enum's because of the visibilities given to its fields.
+    fn narrowed_visibility(&self, variant: &Variant) -> Result<Option<Visibility>> {
+        if !self.options.vis_from_fields {
+            return Ok(None);
+        }
+        let mut narrowest = None;
+        for field in &variant.fields {
+            if let Some(vis) = fields::declared_visibility(field)? {
+                let current = narrowest.as_ref().unwrap_or(&self.vis);
+                if visibility_rank(&vis) < visibility_rank(current) {
+                    narrowest = Some(vis);
+                }
+            }
+        }
+        Ok(narrowest)
+    }
+
+    /// Returns the name of a method of the enum about `variant`: its name in `snake_case`, or the one
+    /// given by its `#[accessor_name(...)]` attribute, after `prefix`.
+    fn accessor_ident(&self, variant: &Variant, prefix: &str) -> Result<Ident> {
+        let mut name = None;
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("accessor_name"))
+        {
+            if name.is_some() {
+                return Err(Error::new_spanned(
+                    attr.pound_token,
+                    "duplicate #[accessor_name] attribute",
+                ));
+            }
+            name = Some(attr.parse_args::<Ident>()?.to_string());
+        }
+        let name = format!(
+            "{}{}",
+            prefix,
+            name.unwrap_or_else(|| snake_case(&variant.ident.to_string()))
+        );
+        // Without a prefix, the name of the variant may well be a keyword, e.g. `Type`
+        Ok(match syn::parse_str::<Ident>(&name) {
+            Ok(_) => Ident::new(&name, variant.ident.span()),
+            Err(_) => Ident::new_raw(&name, variant.ident.span()),
+        })
+    }
+
+    /// Returns the attributes of the `#[variant_attrs(...)]` attribute of `variant`, if it has one.
+    fn variant_attrs(&self, variant: &Variant) -> Result<VariantAttrs> {
+        let mut attrs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("variant_attrs"));
+        let Some(attr) = attrs.next() else {
+            return Ok(VariantAttrs::default());
+        };
+        if let Some(duplicate) = attrs.next() {
+            return Err(Error::new_spanned(
+                duplicate.pound_token,
+                "duplicate #[variant_attrs] attribute",
+            ));
+        }
+        attr.parse_args_with(parse_targeted_attrs)
+    }
+
+    /// Returns the name of the kind enum generated with `kind`.
+    fn kind_ident(&self) -> Ident {
+        let enum_ident = &self.input.ident;
+        Ident::new(&format!("{}Kind", enum_ident), enum_ident.span())
+    }
+
+    /// Returns the name of the exploded enum generated with `exploded`.
+    fn exploded_ident(&self) -> Ident {
+        let enum_ident = &self.input.ident;
+        Ident::new(&format!("{}Exploded", enum_ident), enum_ident.span())
+    }
+
+    /// Returns whether `variant` is marked `#[exclude(all)]` or `#[exclude(kind_only)]`, which
+    /// leaves it out of everything that doesn't have to cover every variant, such as the handler.
+    fn is_hidden(&self, variant: &Variant) -> bool {
+        variant.attrs.iter().any(|attr| {
+            attr.path().is_ident("exclude")
+                && attr
+                    .parse_args::<Ident>()
+                    .is_ok_and(|which| which == "all" || which == "kind_only")
+        })
+    }
+
+    /// Returns whether `variant` has a kind, which only `#[exclude(all)]` takes away.
+    fn has_kind(&self, variant: &Variant) -> bool {
+        !variant.attrs.iter().any(|attr| {
+            attr.path().is_ident("exclude")
+                && attr.parse_args::<Ident>().is_ok_and(|which| which == "all")
+        })
+    }
+
+    /// Returns whether `variant` is marked `#[exclude(keep_impls)]`, which only leaves out the
+    /// declaration of its struct, written by hand instead.
+    fn keeps_impls(&self, variant: &Variant) -> bool {
+        variant.attrs.iter().any(|attr| {
+            attr.path().is_ident("exclude")
+                && attr
+                    .parse_args::<Ident>()
+                    .is_ok_and(|which| which == "keep_impls")
+        })
+    }
+
+    /// Returns whether no struct is generated for `variant`, because it is marked with
+    /// `#[exclude]` or is a unit variant under `skip_unit`.
+    fn is_skipped(&self, variant: &Variant) -> bool {
+        if self.keeps_impls(variant) {
+            return false;
+        }
+        (self.options.skip_unit && matches!(variant.fields, Fields::Unit))
+            || variant
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("exclude"))
+    }
+
+    /// Returns the name of the struct generated for `variant`, if it differs from the variant's.
+    fn struct_name(&self, variant: &Variant) -> Option<Ident> {
+        let name = variant.ident.to_string();
+        // Only trim what leaves a valid name behind
+        let trimmed = self
+            .options
+            .trim_prefix
+            .as_ref()
+            .and_then(|trim| name.strip_prefix(&trim.to_string()))
+            .filter(|rest| rest.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+            .unwrap_or(&name);
+        let mut struct_name = format!("{}{}{}", self.prefix, trimmed, self.suffix);
+        if self.options.reserved == Some(Reserved::Suffix)
+            && PRELUDE_NAMES.contains(&struct_name.as_str())
+        {
+            struct_name.push_str("Variant");
+        }
+        (struct_name != name).then(|| Ident::new(&struct_name, variant.ident.span()))
+    }
+}
+
+impl Config {
+    fn fill_options(&mut self, f: impl FnOnce() -> Result<Options>) -> Result<()> {
+        match &self.options {
+            Some(options) => Err(Error::new_spanned(
+                options.0.pound_token,
+                "duplicate #[extract_variant] attribute",
+            )),
+            None => {
+                self.options = Some(f()?);
+                Ok(())
+            }
+        }
+    }
+    fn fill_prefix(&mut self, f: impl FnOnce() -> Result<Prefix>) -> Result<()> {
+        match &self.prefix {
+            Some(prefix) => Err(Error::new_spanned(
+                prefix.0.pound_token,
+                "duplicate #[prefix] attribute",
+            )),
+            None => {
+                self.prefix = Some(f()?);
+                Ok(())
+            }
+        }
+    }
+    fn fill_suffix(&mut self, f: impl FnOnce() -> Result<Suffix>) -> Result<()> {
+        match &self.suffix {
+            Some(suffix) => Err(Error::new_spanned(
+                suffix.0.pound_token,
+                "duplicate #[suffix] attribute",
+            )),
+            None => {
+                self.suffix = Some(f()?);
+                Ok(())
+            }
+        }
+    }
+    fn fill_no_impl(&mut self, f: impl FnOnce() -> Result<NoImpl>) -> Result<()> {
+        match &self.no_impl {
+            Some(no_impl) => Err(Error::new_spanned(
+                no_impl.0.pound_token,
+                "duplicate #[no_impl] attribute",
+            )),
+            None => {
+                self.no_impl = Some(f()?);
+                Ok(())
+            }
+        }
+    }
+
+    /// Merges the standalone attributes into the options given through `#[extract_variant(...)]`.
+    fn into_options(self) -> Result<ExtractVariant> {
+        let mut options = self.options.map(|(_, options)| options).unwrap_or_default();
+        if let Some((attr, prefix)) = self.prefix {
+            if options.prefix.is_some() {
+                return Err(Error::new_spanned(
+                    attr.pound_token,
+                    "prefix is already specified in #[extract_variant]",
+                ));
+            }
+            options.prefix = Some(prefix);
+        }
+        if let Some((attr, suffix)) = self.suffix {
+            if options.suffix.is_some() {
+                return Err(Error::new_spanned(
+                    attr.pound_token,
+                    "suffix is already specified in #[extract_variant]",
+                ));
+            }
+            options.suffix = Some(suffix);
+        }
+        if let Some((attr,)) = self.no_impl {
+            if options.no_impl {
+                return Err(Error::new_spanned(
+                    attr.pound_token,
+                    "no_impl is already specified in #[extract_variant]",
+                ));
+            }
+            options.no_impl = true;
+        }
+        Ok(options)
+    }
+}
@@ -0,0 +1,57 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Error, Path, Result};
+
+use super::Context;
+use crate::snake_case;
+
+/// Generates an `assert_{enum}_variants!` macro, which fails to compile unless it's given exactly
+/// the variants of the enum, for code that handles each variant outside of a `match`.
+///
+/// The variants become the arms of a `match` on the enum, so that a missing variant isn't
+/// exhaustive, an unknown one doesn't resolve, and a repeated one is unreachable. With the path of
+/// the enum from the root of the crate, the macro is exported for other crates to use.
+pub(super) fn generate_assert_macro(ctx: &Context, exported: Option<&Path>) -> Result<TokenStream> {
+    let enum_ident = &ctx.input.ident;
+    let macro_ident = Ident::new(
+        &format!("assert_{}_variants", snake_case(&enum_ident.to_string())),
+        enum_ident.span(),
+    );
+
+    let (attr, enum_path) = match exported {
+        Some(path) => {
+            let mut segments = path.segments.iter();
+            if path.leading_colon.is_some() || segments.next().is_none_or(|s| s.ident != "crate") {
+                return Err(Error::new_spanned(
+                    path,
+                    "`assert_macro` takes the path of the enum from the root of the crate, starting with `crate`",
+                ));
+            }
+            (
+                quote! { #[macro_export] },
+                quote! { $crate #(:: #segments)* },
+            )
+        }
+        None => {
+            let enum_path = &ctx.enum_path;
+            (quote! { #[allow(unused_macros)] }, quote! { #enum_path })
+        }
+    };
+
+    Ok(quote! {
+        #attr
+        macro_rules! #macro_ident {
+            ($($variant:ident),* $(,)?) => {
+                const _: () = {
+                    #[allow(dead_code)]
+                    #[deny(unreachable_patterns)]
+                    fn assert_variants(value: #enum_path) {
+                        match value {
+                            $(#enum_path::$variant { .. } => {})*
+                        }
+                    }
+                };
+            };
+        }
+    })
+}
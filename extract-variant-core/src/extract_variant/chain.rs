@@ -0,0 +1,64 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Result, Variant};
+
+use super::{fallible, Context};
+
+/// Generates the conversions of each struct from and into an outer enum with a variant wrapping
+/// this enum, given as `chain(Outer::Variant)`, going through this enum: `From<Struct>` on the
+/// outer enum, `TryFrom<Outer>` on the struct, giving back the outer enum if it holds another
+/// variant, and `Variant<Outer>`.
+pub(super) fn impl_chains<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<TokenStream> {
+    let enum_path = &ctx.enum_path;
+
+    let mut struct_idents = Vec::new();
+    for variant in variants {
+        // Fallible variants can't be converted into the enum without their validator
+        if ctx.is_skipped(variant) || fallible::fallible_from(ctx, variant)?.is_some() {
+            continue;
+        }
+        struct_idents.push(
+            ctx.struct_name(variant)
+                .unwrap_or_else(|| variant.ident.clone()),
+        );
+    }
+
+    let mut output = TokenStream::new();
+    for chain in &ctx.options.chain {
+        let mut outer_path = chain.clone();
+        let wrapper = outer_path.segments.pop().unwrap().into_value().ident;
+        outer_path.segments.pop_punct();
+        if outer_path.segments.is_empty() {
+            return Err(Error::new_spanned(
+                chain,
+                "`chain` takes the variant of the outer enum wrapping this one, e.g. `Outer::Inner`",
+            ));
+        }
+        for struct_ident in &struct_idents {
+            output.extend(quote! {
+                impl ::std::convert::From<#struct_ident> for #outer_path {
+                    fn from(variant: #struct_ident) -> Self {
+                        #outer_path::#wrapper(<#enum_path as ::std::convert::From<#struct_ident>>::from(variant))
+                    }
+                }
+                impl ::std::convert::TryFrom<#outer_path> for #struct_ident {
+                    type Error = #outer_path;
+                    fn try_from(value: #outer_path) -> ::std::result::Result<Self, Self::Error> {
+                        match value {
+                            #outer_path::#wrapper(inner) => {
+                                <#struct_ident as ::std::convert::TryFrom<#enum_path>>::try_from(inner)
+                                    .map_err(#outer_path::#wrapper)
+                            }
+                            value => ::std::result::Result::Err(value),
+                        }
+                    }
+                }
+                impl ::variant_traits::Variant<#outer_path> for #struct_ident {}
+            });
+        }
+    }
+    Ok(output)
+}
@@ -0,0 +1,52 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Result, Variant};
+
+use super::{variant_values, Context};
+use crate::wildcard_stream;
+
+/// Generates a `CODE` constant on each extracted struct from the `#[code(...)]` attributes of the
+/// variants, and a `code` method on the enum returning the code of any variant.
+///
+/// Nothing is generated if no variant has a code.
+pub(super) fn generate_codes<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<TokenStream> {
+    let Some(codes) = variant_values(variants, "code")? else {
+        return Ok(TokenStream::new());
+    };
+    let enum_path = &ctx.enum_path;
+    let vis = &ctx.struct_vis;
+
+    let mut output = TokenStream::new();
+    let mut arms = Vec::with_capacity(codes.len());
+    for (variant, code) in &codes {
+        let variant_ident = &variant.ident;
+        if !ctx.is_skipped(variant) {
+            let struct_ident = ctx
+                .struct_name(variant)
+                .unwrap_or_else(|| variant_ident.clone());
+            output.extend(quote! {
+                impl #struct_ident {
+                    /// The code of the variant, as given by its `#[code(...)]` attribute.
+                    #vis const CODE: u16 = #code;
+                }
+            });
+        }
+        let pattern = wildcard_stream(&variant.fields);
+        arms.push(quote! { Self::#variant_ident #pattern => #code });
+    }
+
+    output.extend(quote! {
+        impl #enum_path {
+            /// Returns the code of the variant, as given by its `#[code(...)]` attribute.
+            #vis fn code(&self) -> u16 {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    });
+    Ok(output)
+}
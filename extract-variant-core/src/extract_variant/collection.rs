@@ -0,0 +1,55 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Fields, Ident, Result, Variant};
+
+/// Implements `IntoIterator` (by value and by reference), `FromIterator` and `Deref` on the struct
+/// of a variant marked `#[into_iterator]`, delegating to its single field, so that the struct can
+/// be used like the collection it wraps.
+pub(super) fn impl_collection(
+    variant: &Variant,
+    struct_ident: &Ident,
+    fields: &Fields,
+) -> Result<TokenStream> {
+    let ty = match fields {
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => &unnamed.unnamed[0].ty,
+        _ => {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                "`into_iterator` requires a variant with a single unnamed field",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl ::std::iter::IntoIterator for #struct_ident {
+            type Item = <#ty as ::std::iter::IntoIterator>::Item;
+            type IntoIter = <#ty as ::std::iter::IntoIterator>::IntoIter;
+            fn into_iter(self) -> Self::IntoIter {
+                ::std::iter::IntoIterator::into_iter(self.0)
+            }
+        }
+        impl<'a> ::std::iter::IntoIterator for &'a #struct_ident
+        where
+            &'a #ty: ::std::iter::IntoIterator,
+        {
+            type Item = <&'a #ty as ::std::iter::IntoIterator>::Item;
+            type IntoIter = <&'a #ty as ::std::iter::IntoIterator>::IntoIter;
+            fn into_iter(self) -> Self::IntoIter {
+                ::std::iter::IntoIterator::into_iter(&self.0)
+            }
+        }
+        impl<A> ::std::iter::FromIterator<A> for #struct_ident
+        where
+            #ty: ::std::iter::FromIterator<A>,
+        {
+            fn from_iter<I: ::std::iter::IntoIterator<Item = A>>(iter: I) -> Self {
+                #struct_ident(::std::iter::FromIterator::from_iter(iter))
+            }
+        }
+        impl ::std::ops::Deref for #struct_ident {
+            type Target = #ty;
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+    })
+}
@@ -0,0 +1,127 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Error, Result, Variant};
+
+use super::{fallible, Context};
+
+/// Lists the types the expansion declares and the conversions it implements, as enabled by the
+/// options, before anything is generated, so that two features generating the same item are
+/// reported as a configuration error rather than as conflicting definitions or implementations
+/// in the expanded code.
+///
+/// Paths are compared as written, so a type named through two different paths isn't caught.
+pub(super) fn check_conflicts<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<()> {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let mut plan = Plan::default();
+
+    if !ctx.foreign {
+        plan.declare(enum_ident.to_string(), "the enum".to_owned())?;
+    }
+    let generated = [
+        (ctx.options.kind, ctx.kind_ident(), "`kind`"),
+        (ctx.options.exploded, ctx.exploded_ident(), "`exploded`"),
+        (
+            ctx.options.handler,
+            suffixed(enum_ident, "Handler"),
+            "`handler`",
+        ),
+        (ctx.options.seal, suffixed(enum_ident, "Variant"), "`seal`"),
+    ];
+    for (_, ident, option) in generated.iter().filter(|(enabled, _, _)| *enabled) {
+        plan.declare(
+            ident.to_string(),
+            format!("the type generated by {}", option),
+        )?;
+    }
+
+    for variant in variants {
+        if ctx.is_skipped(variant) {
+            continue;
+        }
+        let struct_ident = ctx
+            .struct_name(variant)
+            .unwrap_or_else(|| variant.ident.clone());
+        let origin = format!("the struct of `{}`", variant.ident);
+        if !ctx.keeps_impls(variant) {
+            plan.declare(struct_ident.to_string(), origin.clone())?;
+        }
+
+        let fallible = fallible::fallible_from(ctx, variant)?.is_some();
+        if !ctx.options.no_impl {
+            let conversion = if fallible {
+                quote!(TryFrom)
+            } else {
+                quote!(From)
+            };
+            plan.implement(quote!(#conversion<#struct_ident>), enum_path, &origin)?;
+            plan.implement(quote!(TryFrom<#enum_path>), &struct_ident, &origin)?;
+        }
+        if !fallible && !ctx.options.no_impl {
+            for chain in &ctx.options.chain {
+                let mut outer_path = chain.clone();
+                outer_path.segments.pop();
+                outer_path.segments.pop_punct();
+                let origin = format!("`chain({})`", chain.to_token_stream());
+                plan.implement(quote!(From<#struct_ident>), &outer_path, &origin)?;
+                plan.implement(quote!(TryFrom<#outer_path>), &struct_ident, &origin)?;
+            }
+        }
+        if let Some(value_path) = &ctx.options.dynamic {
+            let origin = format!("`dynamic({})`", value_path.to_token_stream());
+            plan.implement(quote!(From<#struct_ident>), value_path, &origin)?;
+            plan.implement(quote!(TryFrom<#value_path>), &struct_ident, &origin)?;
+        }
+    }
+    Ok(())
+}
+
+/// The items planned so far, by name, along with what generates them.
+#[derive(Default)]
+struct Plan {
+    types: Vec<(String, String)>,
+    impls: Vec<(String, String)>,
+}
+
+impl Plan {
+    fn declare(&mut self, name: String, origin: String) -> Result<()> {
+        if let Some((_, other)) = self.types.iter().find(|(planned, _)| *planned == name) {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "`{}` would be declared twice, as {} and as {}",
+                    name, other, origin
+                ),
+            ));
+        }
+        self.types.push((name, origin));
+        Ok(())
+    }
+
+    fn implement(&mut self, trait_: TokenStream, ty: &impl ToTokens, origin: &str) -> Result<()> {
+        let compact = |tokens: TokenStream| tokens.to_string().replace(' ', "");
+        let name = format!(
+            "`{}` for `{}`",
+            compact(trait_),
+            compact(ty.to_token_stream())
+        );
+        if let Some((_, other)) = self.impls.iter().find(|(planned, _)| *planned == name) {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "{} would be implemented twice, by {} and by {}",
+                    name, other, origin
+                ),
+            ));
+        }
+        self.impls.push((name, origin.to_owned()));
+        Ok(())
+    }
+}
+
+fn suffixed(ident: &Ident, suffix: &str) -> Ident {
+    Ident::new(&format!("{}{}", ident, suffix), ident.span())
+}
@@ -0,0 +1,119 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Error, Fields, LitStr, Result, Variant};
+
+use super::{fields::struct_fields, Context};
+use crate::{fields_stream, wildcard_stream, Conversion};
+
+/// Implements `Display` on the enum and on the structs from the `#[display_fmt("...")]` attributes of
+/// the variants, whose format strings refer to the fields by name, or as `_0`, `_1`, ... for tuple
+/// variants. The enum is formatted by reference, as its structs would be. Variants marked
+/// `#[display_skip]`, or without a struct, and unit variants without a format are written as their
+/// name.
+pub(super) fn impl_display<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<TokenStream> {
+    let enum_path = &ctx.enum_path;
+
+    let mut output = TokenStream::new();
+    let mut arms = Vec::new();
+    for variant in variants {
+        let variant_ident = &variant.ident;
+        let skip = variant
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("display_skip"));
+        let format = display_format(variant)?;
+        let Some(format) = format.filter(|_| !skip && !ctx.is_skipped(variant)) else {
+            if !skip && !ctx.is_skipped(variant) && !variant.fields.is_empty() {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "`display` formats this variant with its `#[display_fmt(\"...\")]` attribute, which it lacks; mark it `#[display_skip]` to write its name instead",
+                ));
+            }
+            let pattern = wildcard_stream(&variant.fields);
+            let name = variant_ident.to_string();
+            arms.push(quote! { Self::#variant_ident #pattern => f.write_str(#name) });
+            continue;
+        };
+        let struct_ident = ctx
+            .struct_name(variant)
+            .unwrap_or_else(|| variant_ident.clone());
+        let (fields, maps) = struct_fields(ctx, variant)?;
+        let struct_pattern = Conversion::new(&variant.fields, &fields, &maps).struct_pattern;
+        let pattern = fields_stream(&variant.fields);
+        arms.push(quote! {
+            Self::#variant_ident #pattern => ::std::write!(f, #format)
+        });
+        output.extend(quote! {
+            impl ::std::fmt::Display for #struct_ident {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    #[allow(unused_variables)]
+                    let #struct_ident #struct_pattern = self;
+                    ::std::write!(f, #format)
+                }
+            }
+        });
+    }
+
+    output.extend(quote! {
+        impl ::std::fmt::Display for #enum_path {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                #[allow(unused_variables)]
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    });
+    Ok(output)
+}
+
+/// Returns the format string of the `#[display_fmt("...")]` attribute of `variant`, if any.
+fn display_format(variant: &Variant) -> Result<Option<LitStr>> {
+    let mut format = None;
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("display_fmt"))
+    {
+        if format.is_some() {
+            return Err(Error::new_spanned(
+                attr.pound_token,
+                "duplicate #[display_fmt] attribute",
+            ));
+        }
+        format = Some(attr.parse_args()?);
+    }
+    Ok(format)
+}
+
+/// Implements `Debug` on the struct of `variant` as if it were the variant of the enum, e.g.
+/// `MyEnum::TupleVariant(42)`, so that a struct on its own tells where it comes from.
+pub(super) fn impl_qualified_debug(
+    ctx: &Context,
+    variant: &Variant,
+    struct_ident: &Ident,
+    fields: &Fields,
+) -> TokenStream {
+    let name = format!("{}::{}", ctx.input.ident, variant.ident);
+    let members = fields.members();
+    let body = match fields {
+        Fields::Named(_) => {
+            let names = fields
+                .iter()
+                .map(|field| field.ident.as_ref().unwrap().to_string());
+            quote! { f.debug_struct(#name)#(.field(#names, &self.#members))*.finish() }
+        }
+        Fields::Unnamed(_) => quote! { f.debug_tuple(#name)#(.field(&self.#members))*.finish() },
+        Fields::Unit => quote! { f.write_str(#name) },
+    };
+    quote! {
+        impl ::std::fmt::Debug for #struct_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                #body
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use syn::{parse_quote, Attribute, Data, Expr, ExprLit, Lit, Meta};
+
+use super::Context;
+
+/// Rewrites the paths starting with `Self` in a doc comment copied from the enum onto a struct,
+/// where `Self` would stand for the struct instead, so that intra-doc links such as
+/// `[Self::Other]` keep pointing at the same items: the struct of the variant if it has one, or
+/// else the enum.
+pub(super) fn rewrite_doc_links(ctx: &Context, attr: &Attribute) -> Attribute {
+    let Meta::NameValue(name_value) = &attr.meta else {
+        return attr.clone();
+    };
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(doc), ..
+    }) = &name_value.value
+    else {
+        return attr.clone();
+    };
+    let text = doc.value();
+    if !text.contains("Self") {
+        return attr.clone();
+    }
+    let rewritten = rewrite(ctx, &text);
+    parse_quote!(#[doc = #rewritten])
+}
+
+fn rewrite(ctx: &Context, text: &str) -> String {
+    let enum_path = ctx
+        .enum_path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("Self") {
+        let after = &rest[start + 4..];
+        let standalone =
+            !rest[..start].ends_with(is_ident_char) && !after.starts_with(is_ident_char);
+        output.push_str(&rest[..start]);
+        if !standalone {
+            output.push_str("Self");
+            rest = after;
+            continue;
+        }
+        // `Self::Variant` becomes the struct of the variant if it has one
+        let name = after.strip_prefix("::").map_or("", |path| {
+            &path[..path.len() - path.trim_start_matches(is_ident_char).len()]
+        });
+        match struct_of(ctx, name) {
+            Some(struct_name) => {
+                output.push_str(&struct_name);
+                rest = &after[2 + name.len()..];
+            }
+            None => {
+                output.push_str(&enum_path);
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Returns the name of the struct generated for the variant named `name`, if there is one.
+fn struct_of(ctx: &Context, name: &str) -> Option<String> {
+    let Data::Enum(data) = &ctx.input.data else {
+        return None;
+    };
+    let variant = data.variants.iter().find(|variant| variant.ident == name)?;
+    if ctx.is_skipped(variant) {
+        return None;
+    }
+    let struct_ident = ctx
+        .struct_name(variant)
+        .unwrap_or_else(|| variant.ident.clone());
+    Some(struct_ident.to_string())
+}
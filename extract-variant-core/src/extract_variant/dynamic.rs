@@ -0,0 +1,37 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Path, Result};
+
+/// Implements the conversions between `ty`, a struct or the enum, and the dynamic type given to
+/// `dynamic` through serde, with the `to_value` and `from_value` functions and the `Error` type of
+/// its module, as in `serde_json`: `From<ty>` on the dynamic type, which panics if `ty` has no
+/// dynamic representation, and `TryFrom<Value>` on `ty`.
+pub(super) fn impl_dynamic(value_path: &Path, ty: &Path) -> Result<TokenStream> {
+    let mut module = value_path.clone();
+    module.segments.pop();
+    module.segments.pop_punct();
+    if module.segments.is_empty() {
+        return Err(Error::new_spanned(
+            value_path,
+            "`dynamic` takes the path of the type within its crate, e.g. `serde_json::Value`",
+        ));
+    }
+    let message = format!(
+        "`{}` can't be represented as a `{}`",
+        ty.segments.last().unwrap().ident,
+        value_path.segments.last().unwrap().ident
+    );
+    Ok(quote! {
+        impl ::std::convert::From<#ty> for #value_path {
+            fn from(value: #ty) -> Self {
+                #module::to_value(value).expect(#message)
+            }
+        }
+        impl ::std::convert::TryFrom<#value_path> for #ty {
+            type Error = #module::Error;
+            fn try_from(value: #value_path) -> ::std::result::Result<Self, Self::Error> {
+                #module::from_value(value)
+            }
+        }
+    })
+}
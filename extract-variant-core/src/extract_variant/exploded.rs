@@ -0,0 +1,109 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{parse_quote, punctuated::Punctuated, Attribute, Path, Result, Token, Variant};
+
+use super::{fields::struct_fields, Context};
+use crate::{fields_stream, Conversion};
+
+/// Generates an enum with the same variants as the original one, where each extracted variant holds
+/// its struct instead of its fields, along with conversions in both directions.
+///
+/// Excluded variants keep their fields as they are, so that the conversions stay lossless.
+pub(super) fn generate_exploded<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<(Ident, TokenStream)> {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let exploded_ident = ctx.exploded_ident();
+    let vis = &ctx.struct_vis;
+    let derives = ctx
+        .derives
+        .iter()
+        .filter_map(|attr| without_derive(attr, "Default"));
+    let doc = format!(
+        "The variants of [`{0}`], with each extracted variant holding its own struct.",
+        enum_ident
+    );
+    let non_exhaustive = ctx.non_exhaustive.then(|| quote! { #[non_exhaustive] });
+
+    let mut exploded_variants = Vec::new();
+    let mut explode_arms = Vec::new();
+    let mut implode_arms = Vec::new();
+    for variant in variants {
+        let variant_ident = &variant.ident;
+        let docs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        let attrs = ctx.variant_attrs(variant)?.exploded;
+        let docs = docs.chain(&attrs);
+        let fields = fields_stream(&variant.fields);
+        if ctx.is_skipped(variant) {
+            let variant_fields = &variant.fields;
+            exploded_variants.push(quote! { #(#docs)* #variant_ident #variant_fields });
+            explode_arms.push(
+                quote! { #enum_path::#variant_ident #fields => Self::#variant_ident #fields },
+            );
+            implode_arms.push(
+                quote! { #exploded_ident::#variant_ident #fields => Self::#variant_ident #fields },
+            );
+        } else {
+            let struct_ident = ctx
+                .struct_name(variant)
+                .unwrap_or_else(|| variant_ident.clone());
+            let (fields, maps) = struct_fields(ctx, variant)?;
+            let Conversion {
+                struct_pattern,
+                struct_value,
+                enum_pattern,
+                enum_value,
+            } = Conversion::new(&variant.fields, &fields, &maps);
+            exploded_variants.push(quote! { #(#docs)* #variant_ident(#struct_ident) });
+            explode_arms.push(quote! { #enum_path::#variant_ident #enum_pattern => Self::#variant_ident(#struct_ident #struct_value) });
+            implode_arms.push(quote! { #exploded_ident::#variant_ident(#struct_ident #struct_pattern) => Self::#variant_ident #enum_value });
+        }
+    }
+
+    let output = quote! {
+        #[doc = #doc]
+        #(#derives)*
+        #non_exhaustive
+        #vis enum #exploded_ident {
+            #(#exploded_variants),*
+        }
+        impl ::std::convert::From<#enum_path> for #exploded_ident {
+            fn from(value: #enum_path) -> Self {
+                match value {
+                    #(#explode_arms,)*
+                }
+            }
+        }
+        impl ::std::convert::From<#exploded_ident> for #enum_path {
+            fn from(value: #exploded_ident) -> Self {
+                match value {
+                    #(#implode_arms,)*
+                }
+            }
+        }
+    };
+    Ok((exploded_ident, output))
+}
+
+/// Returns the given `#[derive(...)]` attribute without the derive `name`, e.g. `Default`, which
+/// cannot be derived for an enum without a `#[default]` unit variant.
+pub(super) fn without_derive(attr: &Attribute, name: &str) -> Option<Attribute> {
+    let paths = attr
+        .parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+        .ok()?;
+    let paths: Punctuated<Path, Token![,]> = paths
+        .into_iter()
+        .filter(|path| !path.is_ident(name))
+        .collect();
+    if paths.is_empty() {
+        return None;
+    }
+    let mut attr = attr.clone();
+    attr.meta = parse_quote! { derive(#paths) };
+    Some(attr)
+}
@@ -0,0 +1,121 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Expr, Path, Result, Token, Type, Variant,
+};
+
+use super::Context;
+use crate::{impl_try_from_enum, parse_expr_tokens, Conversion};
+
+/// The content of a `#[fallible_from(validator = ..., error = ...)]` attribute.
+pub(super) struct FallibleFrom {
+    /// Checks a `&Struct` before it is wrapped into the enum, returning `Result<(), Error>`.
+    validator: Expr,
+    error: Type,
+}
+
+/// Returns the `#[fallible_from(...)]` attribute of `variant`, if it has one.
+pub(super) fn fallible_from(ctx: &Context, variant: &Variant) -> Result<Option<FallibleFrom>> {
+    let mut fallible = None;
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("fallible_from"))
+    {
+        if fallible.is_some() {
+            return Err(Error::new_spanned(
+                attr.pound_token,
+                "duplicate #[fallible_from] attribute",
+            ));
+        }
+        if ctx.options.no_impl {
+            return Err(Error::new_spanned(
+                attr,
+                "`#[fallible_from]` changes the conversions, which `no_impl` disables",
+            ));
+        }
+        // These convert the structs into the enum without going through the validator
+        let unchecked = [
+            ("exploded", ctx.options.exploded),
+            ("sibling_try_from", ctx.options.sibling_try_from),
+            ("generate_tests", ctx.options.generate_tests),
+            ("clap_args", ctx.options.clap_args),
+        ];
+        if let Some((name, _)) = unchecked.iter().find(|(_, enabled)| *enabled) {
+            return Err(Error::new_spanned(
+                attr,
+                format!(
+                    "`#[fallible_from]` replaces the `From` conversion, which `{}` relies on",
+                    name
+                ),
+            ));
+        }
+        fallible = Some(attr.parse_args()?);
+    }
+    Ok(fallible)
+}
+
+/// Implements `TryFrom<Struct>` on the enum, which checks the struct with the validator before
+/// wrapping it, and the usual `TryFrom<Enum>` on the struct. Without a `From` conversion, the struct
+/// isn't a `Variant` of the enum.
+pub(super) fn impl_fallible_variant(
+    ctx: &Context,
+    fallible: &FallibleFrom,
+    struct_ident: &Ident,
+    variant_ident: &Ident,
+    conversion: &Conversion,
+) -> TokenStream {
+    let enum_path = &ctx.enum_path;
+    let struct_path = Path::from(struct_ident.clone());
+    let FallibleFrom { validator, error } = fallible;
+    let Conversion {
+        struct_pattern,
+        enum_value,
+        ..
+    } = conversion;
+    let try_from = impl_try_from_enum(
+        &struct_path,
+        enum_path,
+        variant_ident,
+        conversion,
+        ctx.options.wrong_variant,
+    );
+    quote! {
+        impl ::std::convert::TryFrom<#struct_path> for #enum_path {
+            type Error = #error;
+            fn try_from(value: #struct_path) -> ::std::result::Result<Self, Self::Error> {
+                (#validator)(&value)?;
+                let #struct_path #struct_pattern = value;
+                ::std::result::Result::Ok(Self::#variant_ident #enum_value)
+            }
+        }
+        #try_from
+    }
+}
+
+impl Parse for FallibleFrom {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut validator = None;
+        let mut error = None;
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_ref() {
+                "validator" => validator = Some(parse_expr_tokens(input)?),
+                "error" => error = Some(input.parse()?),
+                _ => return Err(Error::new(key.span(), "expected `validator` or `error`")),
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        match (validator, error) {
+            (Some(validator), Some(error)) => Ok(Self { validator, error }),
+            _ => Err(Error::new(
+                Span::call_site(),
+                "`#[fallible_from]` requires both `validator` and `error`",
+            )),
+        }
+    }
+}
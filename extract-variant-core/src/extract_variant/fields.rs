@@ -0,0 +1,242 @@
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    token, Error, Field, Fields, FieldsUnnamed, GenericArgument, Member, PathArguments, Result,
+    Token, Type, TypePath, Variant, Visibility,
+};
+
+use super::{docs::rewrite_doc_links, nested_visibility, Context};
+use crate::FieldMap;
+
+/// A `field: VariantType => StructType` entry of a `#[map_type(...)]` attribute.
+struct TypeMapping {
+    member: Member,
+    /// The type of the variant's field, only there for readability.
+    _from: Type,
+    to: Type,
+}
+
+/// Returns the fields of the struct generated for `variant`, and how each of them is converted
+/// from and into the corresponding field of the variant.
+pub(super) fn struct_fields(ctx: &Context, variant: &Variant) -> Result<(Fields, Vec<FieldMap>)> {
+    let mut fields = variant.fields.clone();
+    let mut maps = map_fields(&mut fields)?;
+    for field in fields.iter_mut() {
+        field_visibility(ctx, field)?;
+        for attr in field.attrs.iter_mut() {
+            if attr.path().is_ident("doc") {
+                *attr = rewrite_doc_links(ctx, attr);
+            }
+        }
+    }
+
+    let mut tuple = None;
+    for attr in &variant.attrs {
+        if attr.path().is_ident("tuple") {
+            tuple = Some(attr);
+            continue;
+        }
+        if !attr.path().is_ident("map_type") {
+            continue;
+        }
+        let mappings =
+            attr.parse_args_with(Punctuated::<TypeMapping, Token![,]>::parse_terminated)?;
+        for mapping in mappings {
+            let index = fields
+                .iter()
+                .enumerate()
+                .position(|(i, field)| match (&mapping.member, &field.ident) {
+                    (Member::Named(name), Some(ident)) => name == ident,
+                    (Member::Unnamed(index), None) => index.index as usize == i,
+                    _ => false,
+                })
+                .ok_or_else(|| {
+                    Error::new_spanned(&mapping.member, "no such field in this variant")
+                })?;
+            if !matches!(maps[index], FieldMap::Move) {
+                return Err(Error::new_spanned(
+                    &mapping.member,
+                    "a field can only be mapped once",
+                ));
+            }
+            maps[index] = FieldMap::Into;
+            fields.iter_mut().nth(index).unwrap().ty = mapping.to;
+        }
+    }
+
+    // Point each field back to the one it comes from, after its own docs
+    if ctx.options.field_origin_docs {
+        let enum_ident = &ctx.input.ident;
+        let variant_ident = &variant.ident;
+        for (i, field) in fields.iter_mut().enumerate() {
+            let member = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => i.to_string(),
+            };
+            let note = format!(
+                " Corresponds to `{}::{}.{}`.",
+                enum_ident, variant_ident, member
+            );
+            if field.attrs.iter().any(|attr| attr.path().is_ident("doc")) {
+                field.attrs.push(parse_quote!(#[doc = ""]));
+            }
+            field.attrs.push(parse_quote!(#[doc = #note]));
+        }
+    }
+
+    // The wrapper goes around whatever type the other attributes gave the field
+    if let Some(wrap_fields) = &ctx.options.wrap_fields {
+        for (field, map) in fields.iter_mut().zip(&mut maps) {
+            field.ty = wrap_fields.wrap_type(&field.ty);
+            *map = FieldMap::Wrap {
+                inner: Box::new(std::mem::take(map)),
+                wrap: Box::new(wrap_fields.wrap.clone()),
+                unwrap: Box::new(wrap_fields.unwrap.clone()),
+            };
+        }
+    }
+
+    // Fields are named in the other attributes, so they only lose their names at the end
+    if let Some(attr) = tuple {
+        fields = into_unnamed(fields).ok_or_else(|| {
+            Error::new_spanned(attr, "#[tuple] requires a variant with named fields")
+        })?;
+    }
+
+    Ok((fields, maps))
+}
+
+/// Applies the field-level attributes (`#[boxed]` and `#[unboxed]`) to the fields of a variant,
+/// turning them into the fields of its struct, and returns how each of them is converted.
+///
+/// The attributes are removed from the fields, since the struct doesn't know about them.
+fn map_fields(fields: &mut Fields) -> Result<Vec<FieldMap>> {
+    let mut maps = Vec::with_capacity(fields.len());
+    for field in fields.iter_mut() {
+        let mut map = FieldMap::Move;
+        let mut error = None;
+        field.attrs.retain(|attr| {
+            let new_map = if attr.path().is_ident("boxed") {
+                FieldMap::Box
+            } else if attr.path().is_ident("unboxed") {
+                FieldMap::Unbox
+            } else {
+                return true;
+            };
+            if !matches!(map, FieldMap::Move) {
+                error.get_or_insert_with(|| {
+                    Error::new_spanned(attr, "a field can only be mapped once")
+                });
+            }
+            map = new_map;
+            false
+        });
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        match map {
+            FieldMap::Box => {
+                let ty = &field.ty;
+                field.ty = parse_quote!(::std::boxed::Box<#ty>);
+            }
+            FieldMap::Unbox => {
+                field.ty = box_inner(&field.ty).cloned().ok_or_else(|| {
+                    Error::new_spanned(&field.ty, "#[unboxed] requires a field of type `Box<T>`")
+                })?;
+            }
+            FieldMap::Move | FieldMap::Into | FieldMap::Wrap { .. } => {}
+        }
+        maps.push(map);
+    }
+    Ok(maps)
+}
+
+/// Gives `field` the visibility of its `#[vis(...)]` attribute, if any, or a private one under
+/// `readonly`, as seen from where the struct is generated, and removes the attribute.
+fn field_visibility(ctx: &Context, field: &mut Field) -> Result<()> {
+    let vis = match declared_visibility(field)? {
+        Some(vis) => {
+            field.attrs.retain(|attr| !attr.path().is_ident("vis"));
+            vis
+        }
+        // Spelled out, as fields without a visibility are made public
+        None if ctx.options.readonly => parse_quote!(pub(self)),
+        None => return Ok(()),
+    };
+    field.vis = if ctx.options.hygienic {
+        nested_visibility(&vis)
+    } else {
+        vis
+    };
+    Ok(())
+}
+
+/// Returns the visibility given to `field` by its `#[vis(...)]` attribute, as seen from the enum.
+pub(super) fn declared_visibility(field: &Field) -> Result<Option<Visibility>> {
+    let mut vis = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("vis"))
+    {
+        if vis.is_some() {
+            return Err(Error::new_spanned(
+                attr.pound_token,
+                "duplicate #[vis] attribute",
+            ));
+        }
+        vis = Some(match attr.parse_args()? {
+            // Fields without a visibility are public, so private ones are spelled out
+            Visibility::Inherited => parse_quote!(pub(self)),
+            vis => vis,
+        });
+    }
+    Ok(vis)
+}
+
+/// Turns named fields into unnamed ones, in declaration order.
+fn into_unnamed(fields: Fields) -> Option<Fields> {
+    let Fields::Named(named) = fields else {
+        return None;
+    };
+    let mut unnamed = named.named;
+    for field in &mut unnamed {
+        field.ident = None;
+        field.colon_token = None;
+    }
+    Some(Fields::Unnamed(FieldsUnnamed {
+        paren_token: token::Paren(named.brace_token.span),
+        unnamed,
+    }))
+}
+
+/// Returns `T` if `ty` is `Box<T>`.
+fn box_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Box" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.iter().collect::<Vec<_>>().as_slice() {
+        [GenericArgument::Type(inner)] => Some(inner),
+        _ => None,
+    }
+}
+
+impl Parse for TypeMapping {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let member = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let _from = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let to = input.parse()?;
+        Ok(Self { member, _from, to })
+    }
+}
@@ -0,0 +1,136 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Error, Expr, Member, Result, Token, Variant,
+};
+
+use super::{fields::struct_fields, Context};
+use crate::{field_bindings, fields_stream, parse_expr_tokens, Conversion};
+
+/// A `field = expr` entry of a `#[sample(...)]` attribute.
+struct Sample {
+    member: Member,
+    value: Expr,
+}
+
+/// Generates a `sample` constructor on each extracted struct, and with `kind` a `sample_of`
+/// constructor on the enum, for use in tests. Each field is given the value of its `#[sample(...)]`
+/// entry, or its [Default].
+///
+/// Everything is only compiled for tests or with the `fixtures` feature of the crate using the
+/// macro, so that other crates can use them in their own tests, or under the predicate given as
+/// `fixtures(cfg = ...)`.
+pub(super) fn generate_fixtures<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<TokenStream> {
+    let enum_path = &ctx.enum_path;
+    let vis = &ctx.struct_vis;
+    let kind_ident = ctx.kind_ident();
+    let cfg = match &ctx.options.fixtures {
+        Some(Some(predicate)) => quote! { #predicate },
+        _ => quote! { any(test, feature = "fixtures") },
+    };
+
+    let mut output = TokenStream::new();
+    let mut arms = Vec::new();
+    for variant in variants {
+        let variant_ident = &variant.ident;
+        let bindings = sample_bindings(variant)?;
+        if !ctx.is_skipped(variant) {
+            let struct_ident = ctx
+                .struct_name(variant)
+                .unwrap_or_else(|| variant_ident.clone());
+            let (fields, maps) = struct_fields(ctx, variant)?;
+            let struct_value = Conversion::new(&variant.fields, &fields, &maps).struct_value;
+            output.extend(quote! {
+                #[cfg(#cfg)]
+                impl #struct_ident {
+                    /// Builds a sample value, from the `#[sample(...)]` attribute of the variant
+                    /// and the [Default] of the other fields.
+                    #vis fn sample() -> Self {
+                        #bindings
+                        #struct_ident #struct_value
+                    }
+                }
+            });
+        }
+        if !ctx.has_kind(variant) {
+            continue;
+        }
+        let fields = fields_stream(&variant.fields);
+        arms.push(quote! {
+            #kind_ident::#variant_ident => {
+                #bindings
+                Self::#variant_ident #fields
+            }
+        });
+    }
+
+    if ctx.options.kind {
+        output.extend(quote! {
+            #[cfg(#cfg)]
+            impl #enum_path {
+                /// Builds a sample value of the given kind, from the `#[sample(...)]` attribute of
+                /// its variant and the [Default] of the other fields.
+                #vis fn sample_of(kind: #kind_ident) -> Self {
+                    match kind {
+                        #(#arms)*
+                    }
+                }
+            }
+        });
+    }
+    Ok(output)
+}
+
+/// Binds the fields of the variant to their sample value, under the names used by [Conversion].
+fn sample_bindings(variant: &Variant) -> Result<TokenStream> {
+    let mut values = vec![None; variant.fields.len()];
+    for attr in &variant.attrs {
+        if !attr.path().is_ident("sample") {
+            continue;
+        }
+        let samples = attr.parse_args_with(Punctuated::<Sample, Token![,]>::parse_terminated)?;
+        for sample in samples {
+            let index = variant
+                .fields
+                .iter()
+                .enumerate()
+                .position(|(i, field)| match (&sample.member, &field.ident) {
+                    (Member::Named(name), Some(ident)) => name == ident,
+                    (Member::Unnamed(index), None) => index.index as usize == i,
+                    _ => false,
+                })
+                .ok_or_else(|| {
+                    Error::new_spanned(&sample.member, "no such field in this variant")
+                })?;
+            if values[index].is_some() {
+                return Err(Error::new_spanned(
+                    &sample.member,
+                    "duplicate sample for this field",
+                ));
+            }
+            values[index] = Some(sample.value);
+        }
+    }
+
+    let bindings = field_bindings(&variant.fields);
+    let types = variant.fields.iter().map(|field| &field.ty);
+    let values = values.into_iter().map(|value| match value {
+        Some(value) => quote! { #value },
+        None => quote! { ::std::default::Default::default() },
+    });
+    Ok(quote! { #(let #bindings: #types = #values;)* })
+}
+
+impl Parse for Sample {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let member = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = parse_expr_tokens(input)?;
+        Ok(Self { member, value })
+    }
+}
@@ -0,0 +1,309 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{punctuated::Punctuated, Attribute, Error, Fields, Meta, Result, Token, Variant};
+
+use super::{fieldless_reprs, Context};
+use crate::{
+    field_bindings, fields_stream, shaped_stream, wildcard_stream, AccessorStyle, FieldMap,
+};
+
+/// Generates a `to_*` method on the enum (or as named by the `accessors` option), which clones the
+/// variant's fields into its struct.
+pub(super) fn impl_to_getter(
+    ctx: &Context,
+    variant: &Variant,
+    struct_ident: &Ident,
+    struct_fields: &Fields,
+    maps: &[FieldMap],
+) -> Result<TokenStream> {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let variant_ident = &variant.ident;
+    let vis = &ctx.struct_vis;
+    let prefix = match ctx.options.accessor_style.unwrap_or(AccessorStyle::To) {
+        AccessorStyle::To => "to_",
+        AccessorStyle::Get => "get_",
+        AccessorStyle::Plain => "",
+    };
+    let method = ctx.accessor_ident(variant, prefix)?;
+    let doc = format!(
+        "Clones the fields of [`{0}::{1}`] into a [`{2}`], or returns [`None`] if `self` is another variant.",
+        enum_ident, variant_ident, struct_ident
+    );
+    let pattern = fields_stream(&variant.fields);
+    let cloned = cloned_struct_value(variant, struct_fields, maps);
+    Ok(quote! {
+        impl #enum_path {
+            #[doc = #doc]
+            #vis fn #method(&self) -> ::std::option::Option<#struct_ident> {
+                #[allow(unreachable_patterns, unused_variables)]
+                match self {
+                    Self::#variant_ident #pattern => ::std::option::Option::Some(#struct_ident #cloned),
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+/// Generates an `as_*` and an `as_*_mut` method on the enum for each variant with fields, returning
+/// references to its fields, as a tuple unless there is only one, without going through its
+/// struct. Variants excluded entirely are left out, like their kinds.
+pub(super) fn impl_as_getters<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<TokenStream> {
+    let enum_ident = &ctx.input.ident;
+    let vis = &ctx.struct_vis;
+    let mut methods = Vec::new();
+    for variant in variants {
+        if variant.fields.is_empty() || ctx.is_hidden(variant) {
+            continue;
+        }
+        // The type of the tuple can't depend on a configuration
+        if let Some(attr) = variant
+            .fields
+            .iter()
+            .flat_map(|field| &field.attrs)
+            .find(|attr| attr.path().is_ident("cfg"))
+        {
+            return Err(Error::new_spanned(
+                attr,
+                "`as_getters` returns the fields as a tuple, which can't leave out a field configured out with `#[cfg]`",
+            ));
+        }
+        let variant_ident = &variant.ident;
+        let method = ctx.accessor_ident(variant, "as_")?;
+        let method_mut = format_ident!("{}_mut", method);
+        let pattern = fields_stream(&variant.fields);
+        let bindings = field_bindings(&variant.fields);
+        let types: Vec<_> = variant.fields.iter().map(|field| &field.ty).collect();
+        let (refs, refs_mut, value) = if let [ty] = types.as_slice() {
+            (
+                quote! { &#ty },
+                quote! { &mut #ty },
+                quote! { #(#bindings)* },
+            )
+        } else {
+            (
+                quote! { (#(&#types,)*) },
+                quote! { (#(&mut #types,)*) },
+                quote! { (#(#bindings,)*) },
+            )
+        };
+        let doc = format!(
+            "Returns references to the fields of [`{0}::{1}`], or [`None`] if `self` is another variant.",
+            enum_ident, variant_ident
+        );
+        let doc_mut = format!(
+            "Returns mutable references to the fields of [`{0}::{1}`], or [`None`] if `self` is another variant.",
+            enum_ident, variant_ident
+        );
+        methods.push(quote! {
+            #[doc = #doc]
+            #vis fn #method(&self) -> ::std::option::Option<#refs> {
+                #[allow(unreachable_patterns)]
+                match self {
+                    Self::#variant_ident #pattern => ::std::option::Option::Some(#value),
+                    _ => ::std::option::Option::None,
+                }
+            }
+            #[doc = #doc_mut]
+            #vis fn #method_mut(&mut self) -> ::std::option::Option<#refs_mut> {
+                #[allow(unreachable_patterns)]
+                match self {
+                    Self::#variant_ident #pattern => ::std::option::Option::Some(#value),
+                    _ => ::std::option::Option::None,
+                }
+            }
+        });
+    }
+    if methods.is_empty() {
+        return Ok(TokenStream::new());
+    }
+    let enum_path = &ctx.enum_path;
+    Ok(quote! {
+        impl #enum_path {
+            #(#methods)*
+        }
+    })
+}
+
+/// Generates an `unsafe` `as_*_unchecked` method on a `#[repr(C)]` enum, which borrows the
+/// payload of the variant in place as its struct, without checking that `self` is this variant.
+///
+/// The enum is laid out as its tag, a field-less `#[repr(C)]` enum with the same representation
+/// and discriminants, followed by a union of `#[repr(C)]` structs with the fields of each variant,
+/// which is what the struct is when its fields are those of the variant and its only `repr` is `C`.
+pub(super) fn impl_unchecked_getter(
+    ctx: &Context,
+    variant: &Variant,
+    struct_ident: &Ident,
+    struct_attrs: &[Attribute],
+    struct_fields: &Fields,
+    maps: &[FieldMap],
+) -> Result<TokenStream> {
+    let same_types =
+        variant.fields.len() == struct_fields.len()
+            && variant.fields.iter().zip(struct_fields).all(|(a, b)| {
+                a.ty.to_token_stream().to_string() == b.ty.to_token_stream().to_string()
+            });
+    if !same_types || !maps.iter().all(|map| matches!(map, FieldMap::Move)) {
+        return Err(Error::new_spanned(
+            &variant.ident,
+            "`unchecked_getters` borrows the struct in place, which needs it to have the fields of the variant, unchanged",
+        ));
+    }
+    // `packed`, `align(..)` and the like would move the fields away from those of the payload
+    for attr in struct_attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        let hints = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        if !hints
+            .iter()
+            .all(|hint| matches!(hint, Meta::Path(path) if path.is_ident("C")))
+        {
+            return Err(Error::new_spanned(
+                attr,
+                "`unchecked_getters` borrows the struct in place, which needs it to be `#[repr(C)]` only",
+            ));
+        }
+    }
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let variant_ident = &variant.ident;
+    let vis = &ctx.struct_vis;
+    let method = format_ident!("{}_unchecked", ctx.accessor_ident(variant, "as_")?);
+    let reprs = fieldless_reprs(ctx.input)?;
+    let tags = ctx.variants().iter().map(|variant| {
+        let ident = &variant.ident;
+        let discriminant = variant
+            .discriminant
+            .as_ref()
+            .map(|(eq, expr)| quote! { #eq #expr });
+        quote! { #ident #discriminant }
+    });
+    let pattern = wildcard_stream(&variant.fields);
+    let doc = format!(
+        "Borrows the fields of [`{0}::{1}`] in place as a [`{2}`], without checking the variant.",
+        enum_ident, variant_ident, struct_ident
+    );
+    let safety = format!("`self` must be a [`{0}::{1}`].", enum_ident, variant_ident);
+    Ok(quote! {
+        impl #enum_path {
+            #[doc = #doc]
+            ///
+            /// # Safety
+            ///
+            #[doc = #safety]
+            #vis unsafe fn #method(&self) -> &#struct_ident {
+                #(#reprs)*
+                #[allow(dead_code)]
+                enum Tag {
+                    #(#tags),*
+                }
+                ::std::debug_assert!(::std::matches!(self, Self::#variant_ident #pattern));
+                // The union of the payloads follows the tag, aligned like the enum
+                let offset =
+                    ::std::mem::size_of::<Tag>().next_multiple_of(::std::mem::align_of::<Self>());
+                unsafe {
+                    &*(self as *const Self)
+                        .cast::<u8>()
+                        .add(offset)
+                        .cast::<#struct_ident>()
+                }
+            }
+        }
+    })
+}
+
+/// Implements `TryFrom<Arc<Enum>>` on the struct, which clones the variant's fields out of the shared
+/// enum, or gives the `Arc` back if it holds another variant.
+pub(super) fn impl_try_from_arc(
+    ctx: &Context,
+    variant: &Variant,
+    struct_ident: &Ident,
+    struct_fields: &Fields,
+    maps: &[FieldMap],
+) -> TokenStream {
+    let enum_path = &ctx.enum_path;
+    let variant_ident = &variant.ident;
+    let pattern = fields_stream(&variant.fields);
+    let cloned = cloned_struct_value(variant, struct_fields, maps);
+    quote! {
+        impl ::std::convert::TryFrom<::std::sync::Arc<#enum_path>> for #struct_ident {
+            type Error = ::std::sync::Arc<#enum_path>;
+            fn try_from(
+                value: ::std::sync::Arc<#enum_path>,
+            ) -> ::std::result::Result<Self, Self::Error> {
+                #[allow(unreachable_patterns, unused_variables)]
+                match &*value {
+                    #enum_path::#variant_ident #pattern => {
+                        ::std::result::Result::Ok(#struct_ident #cloned)
+                    }
+                    _ => ::std::result::Result::Err(value),
+                }
+            }
+        }
+    }
+}
+
+/// Generates the accessors of a struct whose fields are private under `readonly`: a getter and a
+/// `_mut` getter for each field, named after it (`_0`, `_1`... for tuple structs), and `into_parts`
+/// returning all the fields as a tuple.
+pub(super) fn impl_readonly_accessors(
+    ctx: &Context,
+    struct_ident: &Ident,
+    struct_fields: &Fields,
+) -> TokenStream {
+    if struct_fields.is_empty() {
+        return TokenStream::new();
+    }
+    let vis = &ctx.struct_vis;
+    let members: Vec<_> = struct_fields.members().collect();
+    let getters: Vec<_> = struct_fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("_{}", i),
+        })
+        .collect();
+    let getters_mut = getters.iter().map(|getter| format_ident!("{}_mut", getter));
+    let types: Vec<_> = struct_fields.iter().map(|field| &field.ty).collect();
+    quote! {
+        impl #struct_ident {
+            #(
+                /// Returns a reference to the field.
+                #vis fn #getters(&self) -> &#types {
+                    &self.#members
+                }
+                /// Returns a mutable reference to the field.
+                #vis fn #getters_mut(&mut self) -> &mut #types {
+                    &mut self.#members
+                }
+            )*
+            /// Returns the fields of the struct, in declaration order.
+            #vis fn into_parts(self) -> (#(#types,)*) {
+                (#(self.#members,)*)
+            }
+        }
+    }
+}
+
+/// Builds the fields of a struct, in its shape, from clones of the fields of its variant bound by
+/// reference with [fields_stream].
+pub(super) fn cloned_struct_value(
+    variant: &Variant,
+    struct_fields: &Fields,
+    maps: &[FieldMap],
+) -> TokenStream {
+    shaped_stream(
+        struct_fields,
+        field_bindings(&variant.fields)
+            .iter()
+            .zip(maps)
+            .map(|(binding, map)| map.to_struct(quote! { ::std::clone::Clone::clone(#binding) })),
+    )
+}
@@ -0,0 +1,84 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Result, Variant};
+
+use super::{fields::struct_fields, Context};
+use crate::{fields_stream, snake_case, wildcard_stream, Conversion};
+
+/// Generates a `{Enum}Handler` trait with an `on_*` method for each variant, which does nothing by
+/// default, and a `dispatch` method on the enum calling the method of its variant.
+///
+/// The methods of extracted variants take their struct, and those of excluded variants take the
+/// enum itself since they have no struct.
+pub(super) fn generate_handler<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<(Ident, TokenStream)> {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let handler_ident = Ident::new(&format!("{}Handler", enum_ident), enum_ident.span());
+    let vis = &ctx.struct_vis;
+    let doc = format!(
+        "Handles the variants of [`{0}`] one by one, through [`{0}::dispatch`]. Every method does nothing by default.",
+        enum_ident
+    );
+
+    let mut methods = Vec::new();
+    let mut arms = Vec::new();
+    for variant in variants {
+        let variant_ident = &variant.ident;
+        let method = Ident::new(
+            &format!("on_{}", snake_case(&variant_ident.to_string())),
+            variant_ident.span(),
+        );
+        let method_doc = format!("Called with a [`{0}::{1}`].", enum_ident, variant_ident);
+        if ctx.is_hidden(variant) {
+            let pattern = wildcard_stream(&variant.fields);
+            arms.push(quote! { Self::#variant_ident #pattern => {} });
+        } else if ctx.is_skipped(variant) {
+            methods.push(quote! {
+                #[doc = #method_doc]
+                #[allow(unused_variables)]
+                fn #method(&mut self, value: #enum_path) {}
+            });
+            let fields = fields_stream(&variant.fields);
+            arms.push(quote! {
+                Self::#variant_ident #fields => handler.#method(Self::#variant_ident #fields)
+            });
+        } else {
+            let struct_ident = ctx
+                .struct_name(variant)
+                .unwrap_or_else(|| variant_ident.clone());
+            let (fields, maps) = struct_fields(ctx, variant)?;
+            let Conversion {
+                struct_value,
+                enum_pattern,
+                ..
+            } = Conversion::new(&variant.fields, &fields, &maps);
+            methods.push(quote! {
+                #[doc = #method_doc]
+                #[allow(unused_variables)]
+                fn #method(&mut self, variant: #struct_ident) {}
+            });
+            arms.push(quote! {
+                Self::#variant_ident #enum_pattern => handler.#method(#struct_ident #struct_value)
+            });
+        }
+    }
+
+    let output = quote! {
+        #[doc = #doc]
+        #vis trait #handler_ident {
+            #(#methods)*
+        }
+        impl #enum_path {
+            /// Calls the method of `handler` that handles the variant.
+            #vis fn dispatch(self, handler: &mut impl #handler_ident) {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    };
+    Ok((handler_ident, output))
+}
@@ -0,0 +1,50 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Variant;
+
+use super::Context;
+use crate::Indices;
+
+/// Generates a `VARIANT_COUNT` constant on the enum, and an `INDEX` constant on each extracted
+/// struct holding the position of its variant in declaration order, among the variants counted.
+pub(super) fn generate_indices<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+    which: Indices,
+) -> TokenStream {
+    let enum_path = &ctx.enum_path;
+    let vis = &ctx.struct_vis;
+
+    let mut output = TokenStream::new();
+    let mut count = 0usize;
+    for variant in variants {
+        let skipped = ctx.is_skipped(variant);
+        if ctx.is_hidden(variant) || (skipped && which == Indices::Extracted) {
+            continue;
+        }
+        if !skipped {
+            let struct_ident = ctx
+                .struct_name(variant)
+                .unwrap_or_else(|| variant.ident.clone());
+            output.extend(quote! {
+                impl #struct_ident {
+                    /// The position of the variant in the enum, in declaration order.
+                    #vis const INDEX: usize = #count;
+                }
+            });
+        }
+        count += 1;
+    }
+
+    let doc = match which {
+        Indices::All => "The number of variants of the enum.",
+        Indices::Extracted => "The number of variants of the enum that are extracted.",
+    };
+    output.extend(quote! {
+        impl #enum_path {
+            #[doc = #doc]
+            #vis const VARIANT_COUNT: usize = #count;
+        }
+    });
+    output
+}
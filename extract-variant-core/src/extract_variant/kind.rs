@@ -0,0 +1,292 @@
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::quote;
+use syn::{Error, LitStr, Result, Variant};
+
+use super::{fieldless_reprs, Context};
+use crate::wildcard_stream;
+
+/// Generates a field-less enum with a variant for each variant of the original one, excluded ones
+/// included, with the same discriminants and representation, a `kind` method on the original
+/// enum returning the kind of any of its variants (also through `HasKind`), and a `KIND` constant
+/// on each extracted struct.
+///
+/// With `kind_index`, the kinds are numbered in declaration order instead, as a `usize`. With
+/// `kind_repr`, the kind enum gets the given representation instead of the enum's, and converts to
+/// and from it. With `compare_by_kind`, the enum can be compared with another value or a kind
+/// regardless of the fields.
+pub(super) fn generate_kind<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<(Ident, TokenStream)> {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let kind_ident = ctx.kind_ident();
+    let vis = &ctx.struct_vis;
+    let index = ctx.options.kind_index;
+    // The discriminants are only valid with the same representation
+    let reprs: Vec<_> = if index {
+        vec![quote! { #[repr(usize)] }]
+    } else if let Some(repr) = &ctx.options.kind_repr {
+        vec![quote! { #[repr(#repr)] }]
+    } else {
+        fieldless_reprs(ctx.input)?
+            .iter()
+            .map(|attr| quote! { #attr })
+            .collect()
+    };
+    let ord = ctx
+        .options
+        .kind_ord
+        .then(|| quote! { #[derive(PartialOrd, Ord)] });
+    let hash = ctx.options.kind_key.then(|| quote! { #[derive(Hash)] });
+    let doc = format!(
+        "The kinds of variants of [`{0}`], without their fields.",
+        enum_ident
+    );
+    // Variants may be added along with those of the enum
+    let non_exhaustive = ctx.non_exhaustive.then(|| quote! { #[non_exhaustive] });
+
+    let variants: Vec<_> = variants.into_iter().collect();
+    // Variants excluded entirely have no kind, which makes it optional
+    let optional = variants.iter().any(|variant| !ctx.has_kind(variant));
+    let mut kind_variants = Vec::new();
+    let mut kind_idents = Vec::new();
+    let mut arms = Vec::new();
+    let mut consts = Vec::new();
+    // The last explicit discriminant and how many variants follow it, to number the kinds after a
+    // variant without one as the enum does
+    let mut last_explicit = None;
+    let mut offset = 0;
+    let mut gap = false;
+    for variant in variants {
+        let variant_ident = &variant.ident;
+        let pattern = wildcard_stream(&variant.fields);
+        let implicit = match &variant.discriminant {
+            Some((_, expr)) => {
+                last_explicit = Some(expr);
+                offset = 0;
+                None
+            }
+            None => {
+                let value = Literal::usize_unsuffixed(offset);
+                Some(match last_explicit {
+                    Some(expr) => quote! { = (#expr) + #value },
+                    None => quote! { = #value },
+                })
+            }
+        };
+        offset += 1;
+        if !ctx.has_kind(variant) {
+            gap = true;
+            arms.push(quote! { Self::#variant_ident #pattern => ::std::option::Option::None });
+            continue;
+        }
+        let attrs = ctx.variant_attrs(variant)?.kind;
+        let docs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .chain(&attrs);
+        // Kinds keep the discriminants of their variants, so that casting them gives the same values
+        let discriminant = if index {
+            let index = kind_variants.len();
+            Some(quote! { = #index })
+        } else if let Some((eq, expr)) = &variant.discriminant {
+            Some(quote! { #eq #expr })
+        } else if gap {
+            // The implicit discriminant would count from the kind before, skipping the gap
+            implicit
+        } else {
+            None
+        };
+        kind_variants.push(quote! { #(#docs)* #variant_ident #discriminant });
+        kind_idents.push(variant_ident);
+        let kind = quote! { #kind_ident::#variant_ident };
+        arms.push(if optional {
+            quote! { Self::#variant_ident #pattern => ::std::option::Option::Some(#kind) }
+        } else {
+            quote! { Self::#variant_ident #pattern => #kind }
+        });
+        if !ctx.is_skipped(variant) {
+            let struct_ident = ctx
+                .struct_name(variant)
+                .unwrap_or_else(|| variant_ident.clone());
+            consts.push(quote! {
+                impl #struct_ident {
+                    /// The kind of the variant.
+                    #vis const KIND: #kind_ident = #kind_ident::#variant_ident;
+                }
+            });
+        }
+    }
+
+    let (kind_type, kind_doc) = if optional {
+        (
+            quote! { ::std::option::Option<#kind_ident> },
+            "Returns the kind of the variant, or `None` for the variants excluded with `#[exclude(all)]`.",
+        )
+    } else {
+        (quote! { #kind_ident }, "Returns the kind of the variant.")
+    };
+
+    let index_methods = index.then(|| {
+        let indices = 0..kind_idents.len();
+        quote! {
+            impl #kind_ident {
+                /// Returns the position of the kind in the enum, in declaration order.
+                #vis const fn index(self) -> usize {
+                    self as usize
+                }
+                /// Returns the kind at the given position in the enum, if there is one.
+                #vis const fn from_index(index: usize) -> ::std::option::Option<Self> {
+                    match index {
+                        #(#indices => ::std::option::Option::Some(Self::#kind_idents),)*
+                        _ => ::std::option::Option::None,
+                    }
+                }
+            }
+        }
+    });
+
+    let repr_methods = ctx.options.kind_repr.as_ref().map(|repr| {
+        let as_repr = Ident::new(&format!("as_{}", repr), repr.span());
+        quote! {
+            impl #kind_ident {
+                /// Returns the discriminant of the kind.
+                #vis const fn #as_repr(self) -> #repr {
+                    self as #repr
+                }
+            }
+            impl ::std::convert::From<#kind_ident> for #repr {
+                fn from(kind: #kind_ident) -> Self {
+                    kind as #repr
+                }
+            }
+            impl ::std::convert::TryFrom<#repr> for #kind_ident {
+                type Error = ::variant_traits::InvalidKindError<#repr>;
+                fn try_from(value: #repr) -> ::std::result::Result<Self, Self::Error> {
+                    #(
+                        if value == Self::#kind_idents as #repr {
+                            return ::std::result::Result::Ok(Self::#kind_idents);
+                        }
+                    )*
+                    ::std::result::Result::Err(::variant_traits::InvalidKindError::new(value))
+                }
+            }
+        }
+    });
+
+    let kind_key = ctx.options.kind_key.then(|| {
+        quote! {
+            impl #enum_path {
+                /// Returns the kind of the variant, to group values by variant in a map whatever
+                /// their fields, the same as `kind()`.
+                #vis fn kind_key(&self) -> #kind_type {
+                    self.kind()
+                }
+            }
+        }
+    });
+
+    let compare_by_kind = ctx.options.compare_by_kind.then(|| {
+        // Excluded variants share a `None` kind, but not their discriminant
+        let kind = if optional {
+            quote! { ::std::option::Option::Some(*other) }
+        } else {
+            quote! { *other }
+        };
+        quote! {
+            impl #enum_path {
+                /// Returns whether both values are the same variant, whatever their fields.
+                #vis fn same_kind(&self, other: &Self) -> bool {
+                    ::std::mem::discriminant(self) == ::std::mem::discriminant(other)
+                }
+            }
+            impl ::std::cmp::PartialEq<#kind_ident> for #enum_path {
+                fn eq(&self, other: &#kind_ident) -> bool {
+                    self.kind() == #kind
+                }
+            }
+        }
+    });
+
+    let output = quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #ord
+        #hash
+        #(#reprs)*
+        #non_exhaustive
+        #vis enum #kind_ident {
+            #(#kind_variants),*
+        }
+        impl #enum_path {
+            #[doc = #kind_doc]
+            #vis fn kind(&self) -> #kind_type {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+        impl ::variant_traits::HasKind for #enum_path {
+            type Kind = #kind_type;
+            fn kind(&self) -> #kind_type {
+                #enum_path::kind(self)
+            }
+        }
+        #index_methods
+        #repr_methods
+        #kind_key
+        #compare_by_kind
+        #(#consts)*
+    };
+    Ok((kind_ident, output))
+}
+
+/// Implements `Display` and `FromStr` on the kind enum, using the name of each variant or the one
+/// given by its `#[kind_name(...)]` attribute.
+pub(super) fn impl_kind_strings<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<TokenStream> {
+    let kind_ident = ctx.kind_ident();
+    let mut variant_idents = Vec::new();
+    let mut names = Vec::new();
+    for variant in variants.into_iter().filter(|variant| ctx.has_kind(variant)) {
+        let mut name = None;
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("kind_name"))
+        {
+            if name.is_some() {
+                return Err(Error::new_spanned(
+                    attr.pound_token,
+                    "duplicate #[kind_name] attribute",
+                ));
+            }
+            name = Some(attr.parse_args::<LitStr>()?.value());
+        }
+        variant_idents.push(&variant.ident);
+        names.push(name.unwrap_or_else(|| variant.ident.to_string()));
+    }
+
+    Ok(quote! {
+        impl ::std::fmt::Display for #kind_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(match self {
+                    #(Self::#variant_idents => #names,)*
+                })
+            }
+        }
+        impl ::std::str::FromStr for #kind_ident {
+            type Err = ::variant_traits::ParseKindError;
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                match s {
+                    #(#names => ::std::result::Result::Ok(Self::#variant_idents),)*
+                    _ => ::std::result::Result::Err(::variant_traits::ParseKindError::new(s)),
+                }
+            }
+        }
+    })
+}
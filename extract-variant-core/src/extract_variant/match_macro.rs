@@ -0,0 +1,58 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Variant;
+
+use super::Context;
+use crate::snake_case;
+
+/// Generates a `match_{enum}!` macro, which matches on a value of the enum with one arm per variant
+/// and binds the extracted struct of the variant, or the enum itself for excluded variants.
+///
+/// The arms become a `match` on the enum, so that a missing variant fails to compile like it would
+/// for a plain `match`.
+pub(super) fn generate_match_macro<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> TokenStream {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let macro_ident = Ident::new(
+        &format!("match_{}", snake_case(&enum_ident.to_string())),
+        enum_ident.span(),
+    );
+
+    let extract_rules = variants.into_iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        if ctx.is_skipped(variant) {
+            quote! { (@extract #variant_ident $value:ident) => { $value }; }
+        } else {
+            let struct_ident = ctx
+                .struct_name(variant)
+                .unwrap_or_else(|| variant_ident.clone());
+            quote! {
+                (@extract #variant_ident $value:ident) => {
+                    match <#struct_ident as ::std::convert::TryFrom<#enum_path>>::try_from($value) {
+                        ::std::result::Result::Ok(variant) => variant,
+                        ::std::result::Result::Err(_) => ::std::unreachable!(),
+                    }
+                };
+            }
+        }
+    });
+
+    quote! {
+        #[allow(unused_macros)]
+        macro_rules! #macro_ident {
+            ($value:expr, { $($variant:ident($binding:pat) => $body:expr),* $(,)? }) => {{
+                let value: #enum_path = $value;
+                match value {
+                    $(#enum_path::$variant { .. } => {
+                        let $binding = #macro_ident!(@extract $variant value);
+                        $body
+                    })*
+                }
+            }};
+            #(#extract_rules)*
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Member, Variant};
+
+use super::Context;
+use crate::{snake_case, wildcard_stream};
+
+/// Generates a `{ENUM}_VARIANTS` static listing a `VariantInfo` for each variant of the enum, with
+/// its name, kind, field names and a constructor from the exploded enum. Variants excluded with
+/// `#[exclude(all)]` have no kind, and aren't listed, unlike those keeping it with
+/// `#[exclude(kind_only)]`.
+pub(super) fn generate_registry<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> (Ident, TokenStream) {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let kind_ident = ctx.kind_ident();
+    let exploded_ident = ctx.exploded_ident();
+    let vis = &ctx.struct_vis;
+    let registry_ident = Ident::new(
+        &format!(
+            "{}_VARIANTS",
+            snake_case(&enum_ident.to_string()).to_uppercase()
+        ),
+        enum_ident.span(),
+    );
+    let doc = format!(
+        "The name, kind, field names and constructor of each variant of [`{0}`].",
+        enum_ident
+    );
+
+    let infos = variants
+        .into_iter()
+        .filter(|variant| ctx.has_kind(variant))
+        .map(|variant| {
+            let variant_ident = &variant.ident;
+            let name = variant_ident.to_string();
+            let field_names = variant.fields.members().map(|member| match member {
+                Member::Named(ident) => ident.to_string(),
+                Member::Unnamed(index) => index.index.to_string(),
+            });
+            // Extracted variants hold their struct in the exploded enum
+            let pattern = if ctx.is_skipped(variant) {
+                wildcard_stream(&variant.fields)
+            } else {
+                quote! { (..) }
+            };
+            quote! {
+                ::variant_traits::VariantInfo::new(
+                    #name,
+                    #kind_ident::#variant_ident,
+                    &[#(#field_names),*],
+                    |exploded| match exploded {
+                        #exploded_ident::#variant_ident #pattern => {
+                            ::std::option::Option::Some(::std::convert::From::from(exploded))
+                        }
+                        #[allow(unreachable_patterns)]
+                        _ => ::std::option::Option::None,
+                    },
+                )
+            }
+        });
+
+    let output = quote! {
+        #[doc = #doc]
+        #vis static #registry_ident: &[::variant_traits::VariantInfo<#enum_path, #kind_ident, #exploded_ident>] = &[
+            #(#infos),*
+        ];
+    };
+    (registry_ident, output)
+}
@@ -0,0 +1,142 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Result, Variant};
+
+use super::{fields::struct_fields, Context};
+use crate::{field_bindings, fields_stream, snake_case};
+
+/// Generates a `#[cfg(test)]` module with a test per extracted variant, converting a value of the
+/// variant from the struct to the enum and back, and from the enum to the struct and back.
+///
+/// The types of the fields are only known by name, so whether they implement `Default` and
+/// `PartialEq` is decided by autoref specialization in the generated code: variants whose fields
+/// can't be defaulted are skipped, and values that can't be compared are only checked for being the
+/// right variant.
+pub(super) fn generate_tests<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<TokenStream> {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let tests: Vec<TokenStream> = variants
+        .into_iter()
+        .filter(|variant| !ctx.is_skipped(variant))
+        .map(|variant| {
+            let variant_ident = &variant.ident;
+            let struct_ident = ctx
+                .struct_name(variant)
+                .unwrap_or_else(|| variant_ident.clone());
+            let test_ident = Ident::new(
+                &format!("round_trip_{}", snake_case(&variant_ident.to_string())),
+                variant_ident.span(),
+            );
+            // The values are built as the struct's fields, which may differ from the variant's
+            let (fields, _) = struct_fields(ctx, variant)?;
+            let defaults = fields
+                .iter()
+                .zip(field_bindings(&fields))
+                .map(|(field, binding)| {
+                    let ty = &field.ty;
+                    quote! { let #binding = (&DefaultProbe::<#ty>::new()).default_value()?; }
+                });
+            let fields = fields_stream(&fields);
+            let lost = format!(
+                "`{0}` did not convert back from `{1}::{2}`",
+                struct_ident, enum_ident, variant_ident
+            );
+            let changed = format!(
+                "`{0}` changed in a round trip through `{1}::{2}`",
+                struct_ident, enum_ident, variant_ident
+            );
+            Ok(quote! {
+                #[test]
+                fn #test_ident() {
+                    let make = || -> ::std::option::Option<#struct_ident> {
+                        #(#defaults)*
+                        ::std::option::Option::Some(#struct_ident #fields)
+                    };
+                    let (::std::option::Option::Some(variant), ::std::option::Option::Some(expected)) = (make(), make()) else {
+                        return;
+                    };
+
+                    let value = <#enum_path as ::std::convert::From<#struct_ident>>::from(variant);
+                    let ::std::result::Result::Ok(variant) = <#struct_ident as ::std::convert::TryFrom<#enum_path>>::try_from(value) else {
+                        panic!(#lost);
+                    };
+                    if let ::std::option::Option::Some(equal) = (&Compare(&variant, &expected)).compare() {
+                        assert!(equal, #changed);
+                    }
+
+                    let value = <#enum_path as ::std::convert::From<#struct_ident>>::from(expected);
+                    let expected = <#enum_path as ::std::convert::From<#struct_ident>>::from(make().unwrap());
+                    let ::std::result::Result::Ok(variant) = <#struct_ident as ::std::convert::TryFrom<#enum_path>>::try_from(value) else {
+                        panic!(#lost);
+                    };
+                    let value = <#enum_path as ::std::convert::From<#struct_ident>>::from(variant);
+                    if let ::std::option::Option::Some(equal) = (&Compare(&value, &expected)).compare() {
+                        assert!(equal, #changed);
+                    }
+                }
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    if tests.is_empty() {
+        return Ok(TokenStream::new());
+    }
+
+    let module = Ident::new(
+        &format!("__extract_variant_tests_{}", enum_ident),
+        enum_ident.span(),
+    );
+    Ok(quote! {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module {
+            use super::*;
+
+            struct DefaultProbe<T>(::std::marker::PhantomData<T>);
+            impl<T> DefaultProbe<T> {
+                fn new() -> Self {
+                    Self(::std::marker::PhantomData)
+                }
+            }
+            trait ViaDefault<T> {
+                fn default_value(&self) -> ::std::option::Option<T>;
+            }
+            impl<T: ::std::default::Default> ViaDefault<T> for DefaultProbe<T> {
+                fn default_value(&self) -> ::std::option::Option<T> {
+                    ::std::option::Option::Some(T::default())
+                }
+            }
+            trait WithoutDefault<T> {
+                fn default_value(&self) -> ::std::option::Option<T>;
+            }
+            impl<T> WithoutDefault<T> for &DefaultProbe<T> {
+                fn default_value(&self) -> ::std::option::Option<T> {
+                    ::std::option::Option::None
+                }
+            }
+
+            struct Compare<'a, T>(&'a T, &'a T);
+            trait ViaPartialEq {
+                fn compare(&self) -> ::std::option::Option<bool>;
+            }
+            impl<T: ::std::cmp::PartialEq> ViaPartialEq for Compare<'_, T> {
+                fn compare(&self) -> ::std::option::Option<bool> {
+                    ::std::option::Option::Some(self.0 == self.1)
+                }
+            }
+            trait WithoutPartialEq {
+                fn compare(&self) -> ::std::option::Option<bool>;
+            }
+            impl<T> WithoutPartialEq for &Compare<'_, T> {
+                fn compare(&self) -> ::std::option::Option<bool> {
+                    ::std::option::Option::None
+                }
+            }
+
+            #(#tests)*
+        }
+    })
+}
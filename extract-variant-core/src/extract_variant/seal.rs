@@ -0,0 +1,52 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Result, Variant};
+
+use super::{fallible, Context};
+
+/// Generates a sealed `{Enum}Variant` trait, implemented by the struct of each variant that
+/// implements `Variant`, so that other crates can take any variant of the enum without being able
+/// to implement the trait themselves.
+///
+/// The `Sealed` supertrait is public, but in a private module, so it can't be named outside of it.
+pub(super) fn generate_seal<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<(Ident, TokenStream)> {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let vis = &ctx.struct_vis;
+    let trait_ident = Ident::new(&format!("{}Variant", enum_ident), enum_ident.span());
+    let module = Ident::new(&format!("__sealed_{}", enum_ident), enum_ident.span());
+    let doc = format!(
+        "Implemented by the struct of each variant of [`{}`], and by no other type.",
+        enum_ident
+    );
+
+    let mut struct_idents = Vec::new();
+    for variant in variants {
+        // Fallible variants don't implement `Variant`
+        if ctx.is_skipped(variant) || fallible::fallible_from(ctx, variant)?.is_some() {
+            continue;
+        }
+        struct_idents.push(
+            ctx.struct_name(variant)
+                .unwrap_or_else(|| variant.ident.clone()),
+        );
+    }
+
+    let output = quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod #module {
+            pub trait Sealed {}
+        }
+        #[doc = #doc]
+        #vis trait #trait_ident: ::variant_traits::Variant<#enum_path> + #module::Sealed {}
+        #(
+            impl #module::Sealed for #struct_idents {}
+            impl #trait_ident for #struct_idents {}
+        )*
+    };
+    Ok((trait_ident, output))
+}
@@ -0,0 +1,23 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+use super::Context;
+
+/// Implements `TryFrom` between every two of the given structs, through the enum.
+pub(super) fn impl_sibling_try_froms(ctx: &Context, struct_idents: &[Ident]) -> TokenStream {
+    let enum_path = &ctx.enum_path;
+    let mut output = TokenStream::new();
+    for from in struct_idents {
+        for to in struct_idents.iter().filter(|to| *to != from) {
+            output.extend(quote! {
+                impl ::std::convert::TryFrom<#from> for #to {
+                    type Error = #enum_path;
+                    fn try_from(value: #from) -> ::std::result::Result<Self, Self::Error> {
+                        ::variant_traits::convert::<#from, #to, #enum_path>(value)
+                    }
+                }
+            });
+        }
+    }
+    output
+}
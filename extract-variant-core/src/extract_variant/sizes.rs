@@ -0,0 +1,54 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Variant;
+
+use super::Context;
+
+/// Generates a `#[cfg(test)]` module with a test printing the size of the enum and of each extracted
+/// struct, largest first.
+pub(super) fn generate_size_report<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> TokenStream {
+    let enum_ident = &ctx.input.ident;
+    let enum_path = &ctx.enum_path;
+    let struct_idents: Vec<_> = variants
+        .into_iter()
+        .filter(|variant| !ctx.is_skipped(variant))
+        .map(|variant| {
+            ctx.struct_name(variant)
+                .unwrap_or_else(|| variant.ident.clone())
+        })
+        .collect();
+    let names = struct_idents.iter().map(Ident::to_string);
+    let count = struct_idents.len();
+    let enum_name = enum_ident.to_string();
+
+    let module = Ident::new(
+        &format!("__extract_variant_sizes_{}", enum_ident),
+        enum_ident.span(),
+    );
+    quote! {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module {
+            use super::*;
+
+            #[test]
+            fn report_sizes() {
+                let mut sizes: [(&str, usize); #count] = [
+                    #((#names, ::std::mem::size_of::<#struct_idents>()),)*
+                ];
+                sizes.sort_by(|a, b| b.1.cmp(&a.1));
+                println!(
+                    "{}: {} bytes",
+                    #enum_name,
+                    ::std::mem::size_of::<#enum_path>()
+                );
+                for (name, size) in sizes {
+                    println!("  {}: {} bytes", name, size);
+                }
+            }
+        }
+    }
+}
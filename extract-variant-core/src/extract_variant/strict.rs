@@ -0,0 +1,117 @@
+use syn::{Attribute, DeriveInput, Error, Result, Variant};
+
+use super::{Context, NESTED_HELPERS};
+
+/// The helper attributes that only configure the enum.
+const ENUM_HELPERS: &[&str] = &["prefix", "suffix", "no_impl"];
+
+/// The helper attributes of a variant that only configure its struct.
+const STRUCT_HELPERS: &[&str] = &[
+    "variant_derive",
+    "impl_attrs",
+    "fallible_from",
+    "tuple",
+    "map_type",
+    "into_iterator",
+    "doc_inherit",
+    "no_doc_inherit",
+];
+
+/// The helper attributes of a field that only configure the field of the struct.
+const FIELD_HELPERS: &[&str] = &["boxed", "unboxed", "vis"];
+
+/// Checks, for `strict`, that no helper attribute is ignored because of where it is: on the enum
+/// for one meant for the variants and the other way around, or on a variant that doesn't get what
+/// the attribute configures.
+pub(super) fn check_strict<'a>(
+    ctx: &Context,
+    input: &DeriveInput,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<()> {
+    if let Some(attr) = find_helper(&input.attrs, NESTED_HELPERS) {
+        return Err(misplaced(
+            attr,
+            "applies to the variants and their fields, and does nothing on the enum",
+        ));
+    }
+    for variant in variants {
+        if let Some(attr) = find_helper(&variant.attrs, ENUM_HELPERS) {
+            return Err(misplaced(
+                attr,
+                "only applies to the enum, and does nothing on a variant",
+            ));
+        }
+        if ctx.is_skipped(variant) {
+            let reason = if find_helper(&variant.attrs, &["exclude"]).is_some() {
+                "`#[exclude]` doesn't extract"
+            } else {
+                "`skip_unit` doesn't extract"
+            };
+            let field_attrs = variant.fields.iter().flat_map(|field| &field.attrs);
+            let attr = find_helper(&variant.attrs, STRUCT_HELPERS)
+                .or_else(|| find_helper(field_attrs, FIELD_HELPERS));
+            if let Some(attr) = attr {
+                return Err(misplaced(
+                    attr,
+                    &format!("configures the struct of the variant, which {}", reason),
+                ));
+            }
+            if !ctx.variant_attrs(variant)?.owned.is_empty() {
+                return Err(Error::new(
+                    variant.ident.span(),
+                    format!(
+                        "the `#[variant_attrs]` meant for the struct do nothing, as {} this variant",
+                        reason
+                    ),
+                ));
+            }
+        }
+        if ctx.keeps_impls(variant) {
+            let reason = "configures the struct of the variant, which `#[exclude(keep_impls)]` leaves to be declared by hand";
+            if let Some(attr) = find_helper(
+                &variant.attrs,
+                &["variant_derive", "doc_inherit", "no_doc_inherit"],
+            ) {
+                return Err(misplaced(attr, reason));
+            }
+            if !ctx.variant_attrs(variant)?.owned.is_empty() {
+                return Err(Error::new(
+                    variant.ident.span(),
+                    "the `#[variant_attrs]` meant for the struct do nothing, as `#[exclude(keep_impls)]` leaves it to be declared by hand",
+                ));
+            }
+        }
+        if !ctx.has_kind(variant) {
+            if let Some(attr) = find_helper(&variant.attrs, &["kind_name", "sample"]) {
+                return Err(misplaced(
+                    attr,
+                    "configures what `#[exclude(all)]` leaves this variant out of",
+                ));
+            }
+            if !ctx.variant_attrs(variant)?.kind.is_empty() {
+                return Err(Error::new(
+                    variant.ident.span(),
+                    "the `#[variant_attrs]` meant for the kind enum do nothing, as `#[exclude(all)]` leaves this variant out of it",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the first of `attrs` that is one of the `helpers`.
+fn find_helper<'a>(
+    attrs: impl IntoIterator<Item = &'a Attribute>,
+    helpers: &[&str],
+) -> Option<&'a Attribute> {
+    attrs.into_iter().find(|attr| {
+        attr.path()
+            .get_ident()
+            .is_some_and(|ident| helpers.contains(&ident.to_string().as_str()))
+    })
+}
+
+fn misplaced(attr: &Attribute, message: &str) -> Error {
+    let name = attr.path().get_ident().unwrap();
+    Error::new_spanned(attr, format!("`#[{}]` {}", name, message))
+}
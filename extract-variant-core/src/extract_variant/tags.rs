@@ -0,0 +1,66 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Result, Variant};
+
+use super::{variant_values, Context};
+
+/// Generates a `TAG` constant on each extracted struct from the `#[tag(...)]` attributes of the
+/// variants. With `kind`, the kind enum also gets a `tag` method and is convertible from a tag.
+///
+/// Nothing is generated if no variant has a tag.
+pub(super) fn generate_tags<'a>(
+    ctx: &Context,
+    variants: impl IntoIterator<Item = &'a Variant>,
+) -> Result<TokenStream> {
+    let Some(tags) = variant_values(variants, "tag")? else {
+        return Ok(TokenStream::new());
+    };
+    let vis = &ctx.struct_vis;
+
+    let mut output = TokenStream::new();
+    for (variant, tag) in &tags {
+        if ctx.is_skipped(variant) {
+            continue;
+        }
+        let struct_ident = ctx
+            .struct_name(variant)
+            .unwrap_or_else(|| variant.ident.clone());
+        output.extend(quote! {
+            impl #struct_ident {
+                /// The tag of the variant, as given by its `#[tag(...)]` attribute.
+                #vis const TAG: u8 = #tag;
+            }
+        });
+    }
+
+    if ctx.options.kind {
+        let kind_ident = ctx.kind_ident();
+        let (variant_idents, tags): (Vec<_>, Vec<_>) = tags
+            .iter()
+            .filter(|(variant, _)| ctx.has_kind(variant))
+            .map(|(variant, tag)| (&variant.ident, tag))
+            .unzip();
+        output.extend(quote! {
+            impl #kind_ident {
+                /// Returns the tag of the kind, as given by the `#[tag(...)]` attribute of its variant.
+                #vis fn tag(self) -> u8 {
+                    match self {
+                        #(Self::#variant_idents => #tags,)*
+                    }
+                }
+            }
+            impl ::std::convert::TryFrom<u8> for #kind_ident {
+                type Error = u8;
+                fn try_from(tag: u8) -> ::std::result::Result<Self, Self::Error> {
+                    #(
+                        if tag == #tags {
+                            return ::std::result::Result::Ok(Self::#variant_idents);
+                        }
+                    )*
+                    ::std::result::Result::Err(tag)
+                }
+            }
+        });
+    }
+    Ok(output)
+}
@@ -0,0 +1,68 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{parse::Parser, AttrStyle, Attribute, Data, Error, Meta, Path, Result};
+
+use crate::{extract_variant::replace_self_tokens, EnumBatch};
+
+pub fn doit(input: EnumBatch) -> Result<TokenStream> {
+    let EnumBatch {
+        shared_attrs,
+        enums,
+    } = input;
+
+    let mut output = TokenStream::new();
+    for mut item in enums {
+        if !matches!(item.data, Data::Enum(_)) {
+            return Err(Error::new_spanned(
+                &item.ident,
+                "`extract_variants!` only takes enums",
+            ));
+        }
+
+        let mut attrs =
+            merge_shared_attrs(&shared_attrs, &item.ident, std::mem::take(&mut item.attrs))?;
+
+        // The derive comes first, so that it sees every attribute of the enum, derives included
+        attrs.insert(
+            0,
+            syn::parse_quote! { #[derive(::extract_variant::extract_variant)] },
+        );
+        item.attrs = attrs;
+        output.extend(quote! { #item });
+    }
+    Ok(output)
+}
+
+/// Puts the `shared` attributes, written as inner attributes, before the own `attrs` of the enum
+/// `ident`, merging the options of both `#[extract_variant(...)]` attributes.
+pub(crate) fn merge_shared_attrs(
+    shared: &[Attribute],
+    ident: &Ident,
+    attrs: Vec<Attribute>,
+) -> Result<Vec<Attribute>> {
+    // `Self` in the shared attributes stands for each enum, e.g. in `prefix(Self)`
+    let enum_path = Path::from(ident.clone());
+    let shared = shared.iter().map(|attr| {
+        let mut attr = attr.clone();
+        attr.style = AttrStyle::Outer;
+        let tokens = replace_self_tokens(quote! { #attr }, &enum_path);
+        Attribute::parse_outer.parse2(tokens)
+    });
+    let mut merged = shared.collect::<Result<Vec<_>>>()?.concat();
+    merged.extend(attrs);
+
+    // The options can only be given once, so the shared ones and those of the enum are merged
+    let (options, mut merged): (Vec<_>, Vec<_>) = merged
+        .into_iter()
+        .partition(|attr| attr.path().is_ident("extract_variant"));
+    let mut args = Vec::new();
+    for attr in &options {
+        if let Meta::List(list) = &attr.meta {
+            args.push(list.tokens.clone());
+        }
+    }
+    if !options.is_empty() {
+        merged.insert(0, syn::parse_quote! { #[extract_variant(#(#args)*)] });
+    }
+    Ok(merged)
+}
@@ -0,0 +1,136 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::ParseStream, parse_quote, punctuated::Punctuated, token, Attribute, Data, DataEnum,
+    DeriveInput, Error, Generics, Meta, Path, Result, Token, Variant,
+};
+
+use crate::{
+    extract_variant::{add_item_attrs, expand},
+    wildcard_stream, ForeignEnum,
+};
+
+/// The most `#[cfg_attr(...)]` attributes the enum can have, as every combination of them is
+/// expanded.
+const MAX_CFG_ATTRS: usize = 4;
+
+pub fn doit(input: ForeignEnum) -> Result<TokenStream> {
+    let ForeignEnum {
+        mut attrs,
+        vis,
+        enum_path,
+        variants,
+        mut rest,
+    } = input;
+
+    // Either way of saying that the list may be incomplete implies the other
+    if attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("non_exhaustive"))
+    {
+        rest = true;
+    } else if rest {
+        attrs.push(parse_quote! { #[non_exhaustive] });
+    }
+
+    let check = shape_check(&enum_path, &variants, rest);
+
+    // Pretend the enum was declared here, under the last segment of its path, so that it goes
+    // through the same expansion as `#[derive(extract_variant)]`
+    let ident = enum_path.segments.last().unwrap().ident.clone();
+    let mut input = DeriveInput {
+        attrs: Vec::new(),
+        vis,
+        ident,
+        generics: Generics::default(),
+        data: Data::Enum(DataEnum {
+            enum_token: token::Enum(Span::call_site()),
+            brace_token: token::Brace(Span::call_site()),
+            variants,
+        }),
+    };
+
+    // Unlike a derive, a macro sees `#[cfg_attr(...)]` as written, with no way of telling which
+    // predicates hold. Each combination is expanded, and only compiled when it's the right one
+    let (cfg_attrs, attrs): (Vec<_>, Vec<_>) = attrs
+        .into_iter()
+        .partition(|attr| attr.path().is_ident("cfg_attr"));
+    if cfg_attrs.len() > MAX_CFG_ATTRS {
+        return Err(Error::new_spanned(
+            &cfg_attrs[MAX_CFG_ATTRS],
+            format!(
+                "`extract_variants_of!` supports at most {} `#[cfg_attr(...)]` attributes",
+                MAX_CFG_ATTRS
+            ),
+        ));
+    }
+    let cfg_attrs = cfg_attrs
+        .iter()
+        .map(|attr| attr.parse_args_with(parse_cfg_attr))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut output = TokenStream::new();
+    for enabled in 0..1usize << cfg_attrs.len() {
+        input.attrs = attrs.clone();
+        let mut predicates = Vec::new();
+        for (i, (predicate, cfg_attrs)) in cfg_attrs.iter().enumerate() {
+            if enabled & 1 << i != 0 {
+                input.attrs.extend(cfg_attrs.iter().cloned());
+                predicates.push(quote! { #predicate });
+            } else {
+                predicates.push(quote! { not(#predicate) });
+            }
+        }
+        let expanded = expand(&input, Some(enum_path.clone()))?;
+        output.extend(if cfg_attrs.is_empty() {
+            expanded
+        } else {
+            add_item_attrs(expanded, &[parse_quote!(#[cfg(all(#(#predicates),*))])])?
+        });
+    }
+    output.extend(check);
+    Ok(output)
+}
+
+/// Parses the content of `#[cfg_attr(predicate, attr, ...)]` into the predicate and the attributes
+/// it applies.
+fn parse_cfg_attr(input: ParseStream) -> Result<(Meta, Vec<Attribute>)> {
+    let predicate = input.parse()?;
+    input.parse::<Token![,]>()?;
+    let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+    let attrs = metas
+        .into_iter()
+        .map(|meta| parse_quote!(#[#meta]))
+        .collect();
+    Ok((predicate, attrs))
+}
+
+/// Matches on the enum with every listed variant, so that the list has to stay in sync with the
+/// actual enum: a missing variant makes the match non-exhaustive, and a variant of the wrong kind
+/// (unit, tuple or struct) doesn't match its pattern. The fields of the extracted variants are
+/// already checked by their conversions.
+///
+/// A trailing `..` in the list adds a wildcard arm, for `#[non_exhaustive]` enums.
+fn shape_check(
+    enum_path: &Path,
+    variants: &Punctuated<Variant, Token![,]>,
+    rest: bool,
+) -> TokenStream {
+    let arms = variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let pattern = wildcard_stream(&variant.fields);
+        quote! { #enum_path::#ident #pattern => {} }
+    });
+    let rest = rest.then(|| quote! { _ => {} });
+    quote! {
+        const _: () = {
+            #[allow(dead_code, unreachable_patterns)]
+            fn check(value: &#enum_path) {
+                match *value {
+                    #(#arms)*
+                    #rest
+                }
+            }
+        };
+    }
+}
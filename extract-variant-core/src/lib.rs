@@ -0,0 +1,1283 @@
+//! The code generation behind the [extract-variant](https://docs.rs/extract-variant) crate.
+//!
+//! A proc-macro crate cannot export anything but its macros, so the actual expansion lives here
+//! and `extract-variant` only forwards its input. With the `testing` feature enabled, the
+//! expansions are also available as plain functions from [TokenStream] to [TokenStream], e.g. for
+//! snapshot testing with `macrotest` or `insta`:
+//!
+//! ```rust, ignore
+//! let expanded = extract_variant_core::extract_variant(quote::quote! {
+//!     enum MyEnum {
+//!         UnitVariant,
+//!         TupleVariant(i32),
+//!     }
+//! });
+//! insta::assert_snapshot!(expanded.to_string());
+//! ```
+//!
+//! Without the `testing` feature, these functions are not considered part of the public API.
+//!
+//! For build scripts and other proc macros, [expand_enum], [expand_foreign_enum] and
+//! [struct_names] are the stable API: they take [syn] types and return errors as [syn::Error]
+//! instead of `compile_error!`, so the caller decides how to report them.
+//!
+//! ```rust, ignore
+//! let input: syn::DeriveInput = syn::parse_quote! {
+//!     #[derive(extract_variant)]
+//!     enum MyEnum {
+//!         UnitVariant,
+//!         TupleVariant(i32),
+//!     }
+//! };
+//! let items = extract_variant_core::expand_enum(&input)?;
+//! ```
+
+#![deny(missing_docs)]
+
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro2::{Group, Ident, Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    braced, parenthesized,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token, Attribute, Data, DataStruct, DeriveInput, Expr, Fields, FieldsNamed, FieldsUnnamed,
+    Generics, LitStr, Meta, Path, Token, Type, TypePath, Variant, Visibility,
+};
+
+mod assert_variant_shape;
+mod extract_variant;
+mod extract_variants;
+mod extract_variants_of;
+mod variant_defaults;
+mod variant_of;
+
+/// A struct that holds the configuration for the [extract_variant] procedural macro.
+#[derive(Default)]
+struct ExtractVariant {
+    /// An optional prefix to be added to the names of the generated structs.
+    prefix: Option<Ident>,
+    /// An optional suffix to be added to the names of the generated structs.
+    suffix: Option<Ident>,
+    /// An optional prefix to be removed from the names of the variants before naming their structs.
+    trim_prefix: Option<Ident>,
+    /// A flag indicating whether the [Into], [TryFrom], and [Variant][variant_traits::Variant] traits should be implemented automatically for the generated structs.
+    no_impl: bool,
+    /// A flag indicating whether the generated items should be placed in a hidden module and re-exported.
+    hygienic: bool,
+    /// A flag indicating whether `to_*` methods cloning a variant into its struct should be generated on the enum.
+    to_getters: bool,
+    /// A flag indicating whether `as_*` and `as_*_mut` methods borrowing the fields of a variant
+    /// should be generated on the enum.
+    as_getters: bool,
+    /// A flag indicating whether `unsafe` `as_*_unchecked` methods borrowing the struct of a
+    /// variant in place should be generated on a `#[repr(C)]` enum.
+    unchecked_getters: bool,
+    /// The module the generated items should be restricted to, as with `pub(in path)`, instead of
+    /// having the visibility of the enum.
+    pub_in: Option<Path>,
+    /// A flag indicating whether the fields of the structs should be private, behind accessors.
+    readonly: bool,
+    /// A flag indicating whether the enum is declared in a function body, where modules can't see it.
+    local: bool,
+    /// A flag indicating whether the structs should be extracted from an `Arc` of the enum, by cloning their fields.
+    from_arc: bool,
+    /// A flag indicating whether an enum holding the generated structs should be generated.
+    exploded: bool,
+    /// A flag indicating whether round-trip tests should be generated for the conversions.
+    generate_tests: bool,
+    /// A flag indicating whether a test printing the sizes of the generated structs should be
+    /// generated.
+    report_sizes: bool,
+    /// A flag indicating whether unit variants should be left out, as if they were excluded.
+    skip_unit: bool,
+    /// A flag indicating whether a field-less `{Enum}Kind` enum should be generated.
+    kind: bool,
+    /// A flag indicating whether the kind enum should implement `Display` and `FromStr`.
+    kind_strings: bool,
+    /// A flag indicating whether the kind enum should be numbered in declaration order, with `index` and `from_index`.
+    kind_index: bool,
+    /// A flag indicating whether the kind enum should implement `PartialOrd` and `Ord`.
+    kind_ord: bool,
+    /// A flag indicating whether the kind enum should implement `Hash`, with a `kind_key` method on
+    /// the enum.
+    kind_key: bool,
+    /// A flag indicating whether the enum should be comparable by kind alone, with a `same_kind`
+    /// method and `PartialEq` against the kind enum.
+    compare_by_kind: bool,
+    /// The integer representation of the kind enum, convertible to and from its kinds, if any.
+    kind_repr: Option<Ident>,
+    /// A flag indicating whether a static listing the metadata of every variant should be generated.
+    registry: bool,
+    /// A flag indicating whether the structs should implement `Debug` as their variant of the enum,
+    /// instead of inheriting its derive.
+    qualified_debug: bool,
+    /// A flag indicating whether helper attributes that would be ignored where they are written
+    /// should be errors.
+    strict: bool,
+    /// A flag indicating whether the enum should implement `Display` through its structs.
+    display: bool,
+    /// The dynamic type, such as `serde_json::Value`, the structs and the enum should be converted
+    /// from and into through serde, if any.
+    dynamic: Option<Path>,
+    /// A flag indicating whether a `{Enum}Handler` trait with a method for each variant should be generated.
+    handler: bool,
+    /// Whether `sample` constructors should be generated for tests, and the `cfg` predicate they
+    /// are compiled under if not the default one.
+    fixtures: Option<Option<Meta>>,
+    /// A flag indicating whether the generated structs should be convertible into each other.
+    sibling_try_from: bool,
+    /// A flag indicating whether a sealed `{Enum}Variant` trait should be implemented by the
+    /// generated structs.
+    seal: bool,
+    /// A flag indicating whether a `match_{enum}!` macro should be generated.
+    match_macro: bool,
+    /// Which variants should be counted and indexed by `VARIANT_COUNT` and `INDEX` constants, if any.
+    indices: Option<Indices>,
+    /// Whether an `assert_{enum}_variants!` macro should be generated, and the path of the enum
+    /// from the root of the crate if it should be exported.
+    assert_macro: Option<Option<Path>>,
+    /// How the methods generated by `to_getters` are named.
+    accessor_style: Option<AccessorStyle>,
+    /// What to do with structs named after a type or trait of the prelude, shadowing them if unset.
+    reserved: Option<Reserved>,
+    /// The order of the variants in the generated lists of them, declaration order if unset.
+    order: Option<Order>,
+    /// A flag indicating whether the settings of `variant_defaults!` in scope should be applied.
+    defaults: bool,
+    /// A flag indicating whether the doc comments of the variants should be left off their structs.
+    no_doc_inherit: bool,
+    /// A flag indicating whether the structs should be no more visible than the fields given a
+    /// visibility with `#[vis(...)]`.
+    vis_from_fields: bool,
+    /// A flag indicating whether each struct field should document the variant field it comes from.
+    field_origin_docs: bool,
+    /// The derives of the enum inherited by the structs, all of them if unset.
+    inherit_derives: Option<Vec<Path>>,
+    /// The traits derived by the structs of unit variants only.
+    unit_derives: Vec<Path>,
+    /// The variants of outer enums wrapping this one, which the structs should convert from and
+    /// into through this enum.
+    chain: Vec<Path>,
+    /// The traits derived by the structs with a single unnamed field only.
+    newtype_derives: Vec<Path>,
+    /// A flag indicating whether `TryFrom` should fail with a `WrongVariant` instead of the enum.
+    wrong_variant: bool,
+    /// A flag indicating whether the generated structs should derive `clap::Args`.
+    clap_args: bool,
+    /// The lints allowed on every generated item.
+    allow: Vec<Path>,
+    /// A flag indicating whether `dead_code` should be allowed on every generated item.
+    allow_unused: bool,
+    /// The generic type wrapping every field of the generated structs, if any.
+    wrap_fields: Option<WrapFields>,
+}
+
+/// The configuration of the `wrap_fields(...)` option.
+struct WrapFields {
+    /// The wrapper type, such as `Spanned<_>`, where `_` stands for the type of the field.
+    wrapper: Type,
+    /// The function wrapping a value, `<Wrapper<_>>::from` by default.
+    wrap: Expr,
+    /// The function unwrapping a value, `<Wrapper<_>>::into_inner` by default.
+    unwrap: Expr,
+}
+
+impl WrapFields {
+    /// Returns the wrapper type around the type of a field.
+    fn wrap_type(&self, ty: &Type) -> Type {
+        let wrapper = &self.wrapper;
+        syn::parse2(replace_infer_tokens(quote! { #wrapper }, ty))
+            .expect("replacing `_` with a type keeps a valid type")
+    }
+}
+
+/// Returns whether `token` is or contains a `_`.
+fn contains_infer(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Ident(ident) => ident == "_",
+        TokenTree::Group(group) => group
+            .stream()
+            .into_iter()
+            .any(|token| contains_infer(&token)),
+        _ => false,
+    }
+}
+
+/// Replaces each `_` in `tokens` with `ty`.
+fn replace_infer_tokens(tokens: TokenStream, ty: &Type) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) if ident == "_" => quote! { #ty },
+            TokenTree::Group(group) => {
+                let mut replaced =
+                    Group::new(group.delimiter(), replace_infer_tokens(group.stream(), ty));
+                replaced.set_span(group.span());
+                quote! { #replaced }
+            }
+            token => quote! { #token },
+        })
+        .collect()
+}
+
+/// The naming conventions of the `accessors(style = "...")` option, for a variant `MyVariant`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AccessorStyle {
+    /// `to_my_variant`, the default.
+    To,
+    /// `get_my_variant`.
+    Get,
+    /// `my_variant`.
+    Plain,
+}
+
+/// What the `reserved(...)` option does with a struct named after a type or trait of the prelude,
+/// such as `String` or `Option`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reserved {
+    /// Reject the name.
+    Error,
+    /// Add `Variant` to the name.
+    Suffix,
+    /// Keep the name, shadowing the prelude, the default.
+    Shadow,
+}
+
+/// The order of the variants in the lists generated from them, set by the `order(...)` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    /// The order of the enum, the default.
+    Declaration,
+    /// Sorted by the names of the variants.
+    Alphabetical,
+}
+
+/// The variants counted by the `indices` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Indices {
+    /// Every variant, extracted or not.
+    All,
+    /// Only the variants that have a struct.
+    Extracted,
+}
+
+/// A struct that holds the configuration for the [variant_of] attribute.
+struct VariantOf {
+    /// The path to the enum that the struct corresponds to.
+    enum_path: Path,
+    /// The name of the variant in the enum that the struct corresponds to.
+    variant_ident: Option<Ident>,
+    /// Whether the conversions match the variant directly, rather than through `into` and `from`.
+    strict: bool,
+    /// The function turning the struct into the enum, with `strict = false`.
+    into: Option<Expr>,
+    /// The function unwrapping the struct from the enum, with `strict = false`.
+    from: Option<Expr>,
+    /// The `marker_only` flag, if given: the conversions are written by hand and only the
+    /// `Variant` marker is implemented.
+    marker_only: Option<Ident>,
+    /// Whether the variant holds the struct in a `Box`, e.g. `Node(Box<NodeData>)`.
+    boxed: bool,
+    /// Whether the single field of the struct converts into and from the single field of the
+    /// variant, rather than having the same type.
+    transparent: bool,
+    /// Whether `matches` and `extract_ref` functions should be generated on the struct.
+    helpers: bool,
+    /// The discriminant of the variant, exposed as a `DISCRIMINANT` constant on a unit struct.
+    discriminant: Option<Expr>,
+    /// The representation of the discriminant, `isize` unless given.
+    repr: Option<Type>,
+}
+
+/// Expands `#[derive(extract_variant)]` on the given enum.
+///
+/// Errors are reported as a `compile_error!` invocation in the returned [TokenStream], just like
+/// the derive macro itself would.
+#[cfg_attr(not(feature = "testing"), doc(hidden))]
+pub fn extract_variant(input: TokenStream) -> TokenStream {
+    match syn::parse2(input).and_then(extract_variant::doit) {
+        Ok(token_stream) => token_stream,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+/// Expands `#[derive(extract_variant)]` on the given enum, with its helper attributes.
+///
+/// The enum itself is not part of the output, only the structs and impls generated for it.
+pub fn expand_enum(input: &DeriveInput) -> syn::Result<TokenStream> {
+    extract_variant::doit(input.clone())
+}
+
+/// Expands the given enum as if it was restated in `extract_variants_of!` with the path
+/// `enum_path`, for an enum declared elsewhere.
+///
+/// The visibility of `input` is the visibility of the generated structs, and its attributes
+/// include the options, as in `extract_variants_of!`.
+pub fn expand_foreign_enum(input: &DeriveInput, enum_path: Path) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "`extract_variants_of` can only be used with enums",
+        ));
+    };
+    extract_variants_of::doit(ForeignEnum {
+        attrs: input.attrs.clone(),
+        vis: input.vis.clone(),
+        enum_path,
+        variants: data.variants.clone(),
+        rest: false,
+    })
+}
+
+/// Returns each variant of the given enum with the name of the struct generated for it, or `None`
+/// if it has none, e.g. because of `#[exclude]` or `skip_unit`.
+///
+/// A variant with `#[exclude(keep_impls)]` is given the name of the struct it expects to find.
+pub fn struct_names(input: &DeriveInput) -> syn::Result<Vec<(Ident, Option<Ident>)>> {
+    extract_variant::struct_names(input)
+}
+
+/// The input of the [extract_variants] macro: enums declared together, sharing the inner
+/// attributes of the block.
+struct EnumBatch {
+    /// The attributes put on each enum, from `#![...]` at the start of the block.
+    shared_attrs: Vec<Attribute>,
+    enums: Vec<DeriveInput>,
+}
+
+/// Expands `extract_variants! { ... }` on the given input.
+///
+/// Errors are reported as a `compile_error!` invocation in the returned [TokenStream], just like
+/// the macro itself would.
+#[cfg_attr(not(feature = "testing"), doc(hidden))]
+pub fn extract_variants(input: TokenStream) -> TokenStream {
+    match syn::parse2(input).and_then(extract_variants::doit) {
+        Ok(token_stream) => token_stream,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+/// The input of the [extract_variants_of] macro: an enum defined elsewhere, restated as its path
+/// followed by its variants.
+struct ForeignEnum {
+    /// The attributes that would be on the enum, such as its derives.
+    attrs: Vec<Attribute>,
+    /// The visibility of the generated structs.
+    vis: Visibility,
+    enum_path: Path,
+    variants: Punctuated<Variant, Token![,]>,
+    /// Whether the variants end with `..`, i.e. the enum may have variants that aren't listed.
+    rest: bool,
+}
+
+/// Expands `extract_variants_of! { ... }` on the given input.
+///
+/// Errors are reported as a `compile_error!` invocation in the returned [TokenStream], just like
+/// the macro itself would.
+#[cfg_attr(not(feature = "testing"), doc(hidden))]
+pub fn extract_variants_of(input: TokenStream) -> TokenStream {
+    match syn::parse2(input).and_then(extract_variants_of::doit) {
+        Ok(token_stream) => token_stream,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+/// Expands `variant_defaults! { ... }` on the given input.
+///
+/// Errors are reported as a `compile_error!` invocation in the returned [TokenStream], just like
+/// the macro itself would.
+#[cfg_attr(not(feature = "testing"), doc(hidden))]
+pub fn variant_defaults(input: TokenStream) -> TokenStream {
+    match syn::parse2(input).and_then(variant_defaults::doit) {
+        Ok(token_stream) => token_stream,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+/// Expands `#[derive(extract_variant)]` on an enum with the `defaults` option, given after the
+/// settings of `variant_defaults!` as inner attributes.
+///
+/// Errors are reported as a `compile_error!` invocation in the returned [TokenStream], just like
+/// the macro itself would.
+#[cfg_attr(not(feature = "testing"), doc(hidden))]
+pub fn apply_variant_defaults(input: TokenStream) -> TokenStream {
+    match syn::parse2(input).and_then(variant_defaults::apply) {
+        Ok(token_stream) => token_stream,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+/// The input of the [assert_variant_shape] macro: a struct, and the variant whose shape it should
+/// have, written as in the declaration of its enum after the path of the enum.
+struct VariantShape {
+    struct_path: Path,
+    enum_path: Path,
+    variant: Variant,
+}
+
+/// Expands `assert_variant_shape!(...)` on the given input.
+///
+/// Errors are reported as a `compile_error!` invocation in the returned [TokenStream], just like
+/// the macro itself would.
+#[cfg_attr(not(feature = "testing"), doc(hidden))]
+pub fn assert_variant_shape(input: TokenStream) -> TokenStream {
+    match syn::parse2(input).and_then(assert_variant_shape::doit) {
+        Ok(token_stream) => token_stream,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+/// Expands `#[derive(Variant)]` on the given struct.
+///
+/// Errors are reported as a `compile_error!` invocation in the returned [TokenStream], just like
+/// the derive macro itself would.
+#[cfg_attr(not(feature = "testing"), doc(hidden))]
+pub fn derive_variant(input: TokenStream) -> TokenStream {
+    match syn::parse2(input).and_then(variant_of::doit) {
+        Ok(token_stream) => token_stream,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+/// Generates a struct definition from a variant of an enum.
+///
+/// # Parameters
+///
+/// - `input`: A reference to a `DeriveInput` struct representing the original enum.
+/// - `variant`: A reference to a `Variant` struct representing the variant to be extracted.
+/// - `struct_name`: The name of the struct to be generated, if it differs from the variant's.
+///
+/// # Returns
+///
+/// A `DeriveInput` struct representing the generated struct definition.
+fn generate_variant(
+    input: &DeriveInput,
+    variant: &Variant,
+    struct_name: Option<Ident>,
+) -> DeriveInput {
+    let mut fields = variant.fields.clone();
+    for field in &mut fields {
+        // Fields given a visibility with `#[vis(...)]` keep it. The others are public, spanned on
+        // their types so that privacy errors point at the variant's field
+        if matches!(field.vis, Visibility::Inherited) {
+            field.vis = Visibility::Public(token::Pub(field.ty.span()));
+        }
+    }
+    DeriveInput {
+        attrs: Vec::new(),
+        vis: input.vis.clone(),
+        ident: struct_name.unwrap_or_else(|| variant.ident.clone()),
+        generics: Generics::default(),
+        data: Data::Struct(DataStruct {
+            struct_token: token::Struct(variant.ident.span()),
+            fields,
+            semi_token: None,
+        }),
+    }
+}
+
+/// Generates a block of code that implements the `Into`, `TryFrom`, and `Variant` traits for a struct that corresponds to a variant of an enum, and `HasVariant` for the enum.
+///
+/// # Parameters
+///
+/// - `struct_ident`: The name of the struct for which the traits should be implemented.
+/// - `conversion`: How the fields of the struct are converted from and into the fields of the variant.
+/// - `enum_path`: A reference to a `Path` struct representing the path to the enum that the struct corresponds to.
+/// - `variant_name`: An optional `Ident` struct representing the name of the variant in the enum that the struct corresponds to. If this parameter is `None`, the function will use the name of the struct as the name of the variant.
+/// - `wrong_variant`: Whether `TryFrom` fails with a `WrongVariant` rather than the enum, in which case `Variant` isn't implemented.
+///
+/// # Returns
+///
+/// A `TokenStream` representing the generated block of code.
+fn impl_variant(
+    struct_ident: &Ident,
+    conversion: &Conversion,
+    enum_path: &Path,
+    variant_ident: Option<&Ident>,
+    wrong_variant: bool,
+) -> TokenStream {
+    let struct_path = Path::from(struct_ident.clone());
+    let variant_ident = variant_ident.unwrap_or(struct_ident);
+
+    // Create the `From` and `TryFrom` trait implementations
+    let froms = impl_froms(
+        &struct_path,
+        enum_path,
+        variant_ident,
+        conversion,
+        wrong_variant,
+    );
+    let traits = impl_variant_traits(&struct_path, enum_path, wrong_variant);
+
+    // Return a `TokenStream` containing the trait implementations
+    quote! {
+        #froms
+        #traits
+    }
+}
+
+/// Implements `Variant` for a struct that already converts from and into the enum, which gives the
+/// enum `HasVariant` through its blanket impl. If `TryFrom` fails with a `WrongVariant`, `Variant`
+/// can't be implemented, so `HasVariant` is implemented instead.
+fn impl_variant_traits(struct_path: &Path, enum_path: &Path, wrong_variant: bool) -> TokenStream {
+    // `Variant` requires the enum itself as the error
+    if !wrong_variant {
+        return quote! { impl ::variant_traits::Variant<#enum_path> for #struct_path {} };
+    }
+    quote! {
+        impl ::variant_traits::HasVariant<#struct_path> for #enum_path {
+            fn insert(variant: #struct_path) -> Self {
+                ::std::convert::From::from(variant)
+            }
+            fn extract(self) -> ::std::result::Result<#struct_path, Self> {
+                ::std::convert::TryFrom::try_from(self)
+                    .map_err(::variant_traits::WrongVariant::into_inner)
+            }
+        }
+    }
+}
+
+fn fields_stream(fields: &Fields) -> TokenStream {
+    match fields {
+        // If the fields are named, bind the names to variables and use them to create the trait implementations
+        Fields::Named(FieldsNamed { named, .. }) => {
+            let names = named.into_iter().map(|f| {
+                let cfgs = cfg_attrs(f);
+                let name = f.ident.as_ref().unwrap();
+                quote! { #(#cfgs)* #name }
+            });
+            quote! { { #(#names),* } }
+        }
+        // If the fields are unnamed, bind the fields to variables with names like "_0", "_1", etc. and use them to create the trait implementations
+        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+            let id = (0..unnamed.len()).map(|i| Ident::new(&format!("_{}", i), Span::call_site()));
+            quote! { (#(#id),*) }
+        }
+        // If the fields are a unit type, bind no variables and use them to create the trait implementations
+        Fields::Unit => quote! {},
+    }
+}
+/// Builds a pattern matching any value in the shape of `fields`, without binding anything.
+fn wildcard_stream(fields: &Fields) -> TokenStream {
+    match fields {
+        Fields::Named(_) => quote! { { .. } },
+        Fields::Unnamed(_) => quote! { (..) },
+        Fields::Unit => quote! {},
+    }
+}
+
+/// Builds a value (or pattern) in the shape of `fields`, with the given values for the fields.
+fn shaped_stream(fields: &Fields, values: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+    let values = values.into_iter();
+    match fields {
+        Fields::Named(FieldsNamed { named, .. }) => {
+            let entries = named.into_iter().zip(values).map(|(f, value)| {
+                let cfgs = cfg_attrs(f);
+                let name = f.ident.as_ref().unwrap();
+                quote! { #(#cfgs)* #name: #value }
+            });
+            quote! { { #(#entries),* } }
+        }
+        Fields::Unnamed(_) => quote! { (#(#values),*) },
+        Fields::Unit => quote! {},
+    }
+}
+
+/// Returns the `#[cfg(...)]` attributes of `field`, which go on each entry of the field in the
+/// patterns and values of its struct or variant, as named fields may be configured out.
+fn cfg_attrs(field: &syn::Field) -> impl Iterator<Item = &Attribute> {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+}
+
+/// Returns the names [fields_stream] binds the fields to, in order.
+fn field_bindings(fields: &Fields) -> Vec<Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => ident.clone(),
+            None => Ident::new(&format!("_{}", i), Span::call_site()),
+        })
+        .collect()
+}
+
+/// Converts a `CamelCase` identifier into `snake_case`, keeping acronyms together
+/// (`HTTPServer` becomes `http_server`).
+fn snake_case(ident: &str) -> String {
+    let chars: Vec<char> = ident.chars().collect();
+    let mut snake = String::with_capacity(ident.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev != '_' && (!prev.is_uppercase() || next_is_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// How the value of a variant's field is turned into the value of its struct's field, and back.
+#[derive(Clone, Default)]
+enum FieldMap {
+    /// The value is moved as is.
+    #[default]
+    Move,
+    /// The struct's field is a `Box` of the variant's field.
+    Box,
+    /// The variant's field is a `Box` of the struct's field.
+    Unbox,
+    /// The fields have different types, converted into each other with [Into].
+    Into,
+    /// The struct's field is the result of `inner`, wrapped by calling `wrap` and unwrapped by
+    /// calling `unwrap`.
+    Wrap {
+        inner: Box<FieldMap>,
+        wrap: Box<Expr>,
+        unwrap: Box<Expr>,
+    },
+}
+
+impl FieldMap {
+    /// Returns an expression turning `value` from the variant's field into the struct's field.
+    fn to_struct(&self, value: TokenStream) -> TokenStream {
+        match self {
+            Self::Move => value,
+            Self::Box => quote! { ::std::boxed::Box::new(#value) },
+            Self::Unbox => quote! { *#value },
+            Self::Into => quote! { ::std::convert::Into::into(#value) },
+            Self::Wrap { inner, wrap, .. } => {
+                let value = inner.to_struct(value);
+                quote! { (#wrap)(#value) }
+            }
+        }
+    }
+    /// Returns an expression turning `value` from the struct's field into the variant's field.
+    fn to_enum(&self, value: TokenStream) -> TokenStream {
+        match self {
+            Self::Move => value,
+            Self::Box => quote! { *#value },
+            Self::Unbox => quote! { ::std::boxed::Box::new(#value) },
+            Self::Into => quote! { ::std::convert::Into::into(#value) },
+            Self::Wrap { inner, unwrap, .. } => inner.to_enum(quote! { (#unwrap)(#value) }),
+        }
+    }
+}
+
+/// The patterns binding the fields of a variant and of its struct, and the expressions building
+/// each of them from the bindings of the other.
+struct Conversion {
+    struct_pattern: TokenStream,
+    struct_value: TokenStream,
+    enum_pattern: TokenStream,
+    enum_value: TokenStream,
+}
+
+impl Conversion {
+    /// The conversion between a variant and a struct with exactly the same fields.
+    fn identity(fields: &Fields) -> Self {
+        let stream = fields_stream(fields);
+        Self {
+            struct_pattern: stream.clone(),
+            struct_value: stream.clone(),
+            enum_pattern: stream.clone(),
+            enum_value: stream,
+        }
+    }
+    /// The conversion between a variant with `variant_fields` and a struct with `struct_fields`,
+    /// where each field of the struct is mapped from the field of the variant at the same position
+    /// by `maps`.
+    fn new(variant_fields: &Fields, struct_fields: &Fields, maps: &[FieldMap]) -> Self {
+        let same_shape = variant_fields.len() == struct_fields.len()
+            && variant_fields
+                .iter()
+                .zip(struct_fields)
+                .all(|(a, b)| a.ident == b.ident);
+        if same_shape && maps.iter().all(|map| matches!(map, FieldMap::Move)) {
+            return Self::identity(variant_fields);
+        }
+        let bindings = field_bindings(variant_fields);
+        let mapped = |f: fn(&FieldMap, TokenStream) -> TokenStream| {
+            bindings
+                .iter()
+                .zip(maps)
+                .map(move |(binding, map)| f(map, quote! { #binding }))
+        };
+        // Struct fields are bound by name to the bindings of the variant's fields, so that they
+        // don't have to keep the same names (or order) to be converted back
+        let struct_pattern = match struct_fields {
+            Fields::Named(FieldsNamed { named, .. }) => {
+                let entries = named.iter().zip(&bindings).map(|(field, binding)| {
+                    let cfgs = cfg_attrs(field);
+                    let name = field.ident.as_ref().unwrap();
+                    if name == binding {
+                        quote! { #(#cfgs)* #name }
+                    } else {
+                        quote! { #(#cfgs)* #name: #binding }
+                    }
+                });
+                quote! { { #(#entries),* } }
+            }
+            _ => shaped_stream(struct_fields, bindings.iter().map(|b| quote! { #b })),
+        };
+        Self {
+            struct_pattern,
+            struct_value: shaped_stream(struct_fields, mapped(FieldMap::to_struct)),
+            enum_pattern: fields_stream(variant_fields),
+            enum_value: shaped_stream(variant_fields, mapped(FieldMap::to_enum)),
+        }
+    }
+}
+
+/// The `const` of the conversion impls, only there with the `nightly_const` feature, as const
+/// trait impls aren't stable yet.
+fn const_impl() -> TokenStream {
+    if cfg!(feature = "nightly_const") {
+        quote! { const }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn impl_froms(
+    struct_path: &Path,
+    enum_path: &Path,
+    variant_ident: &Ident,
+    conversion: &Conversion,
+    wrong_variant: bool,
+) -> TokenStream {
+    let Conversion {
+        struct_pattern,
+        enum_value,
+        ..
+    } = conversion;
+    let try_from = impl_try_from_enum(
+        struct_path,
+        enum_path,
+        variant_ident,
+        conversion,
+        wrong_variant,
+    );
+    let constness = const_impl();
+    quote! {
+        impl #constness ::std::convert::From<#struct_path> for #enum_path {
+            fn from(#struct_path #struct_pattern: #struct_path) -> Self {
+                Self::#variant_ident #enum_value
+            }
+        }
+        #try_from
+    }
+}
+
+/// Implements `TryFrom<Enum>` on the struct, the half of [impl_froms] unwrapping the variant.
+fn impl_try_from_enum(
+    struct_path: &Path,
+    enum_path: &Path,
+    variant_ident: &Ident,
+    conversion: &Conversion,
+    wrong_variant: bool,
+) -> TokenStream {
+    let Conversion {
+        struct_value,
+        enum_pattern,
+        ..
+    } = conversion;
+    let (error, error_value) = if wrong_variant {
+        let expected = variant_ident.to_string();
+        (
+            quote! { ::variant_traits::WrongVariant<#enum_path> },
+            quote! { ::variant_traits::WrongVariant::new(#expected, value) },
+        )
+    } else {
+        (quote! { #enum_path }, quote! { value })
+    };
+    let constness = const_impl();
+    quote! {
+        impl #constness ::std::convert::TryFrom<#enum_path> for #struct_path {
+            type Error = #error;
+            fn try_from(value: #enum_path) -> ::std::result::Result<Self, Self::Error> {
+                if let #enum_path::#variant_ident #enum_pattern = value { Ok(#struct_path #struct_value) } else { Err(#error_value) }
+            }
+        }
+    }
+}
+
+// ================================================================================================
+// ------------------------------------------------------------------------------------------------
+// ================================================================================================
+
+impl Parse for ExtractVariant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut extract_variant = Self::default();
+        while !input.is_empty() {
+            let ident = parse_option_name(input)?;
+            match ident.to_string().as_ref() {
+                "prefix" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.prefix = Some(inner_content.parse()?)
+                }
+                "suffix" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.suffix = Some(inner_content.parse()?)
+                }
+                "dynamic" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.dynamic = Some(inner_content.parse()?)
+                }
+                "pub_in" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.pub_in = Some(inner_content.parse()?)
+                }
+                "trim_prefix" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.trim_prefix = Some(inner_content.parse()?)
+                }
+                "no_impl" => extract_variant.no_impl = true,
+                "hygienic" => extract_variant.hygienic = true,
+                "to_getters" => extract_variant.to_getters = true,
+                "as_getters" => extract_variant.as_getters = true,
+                "unchecked_getters" => extract_variant.unchecked_getters = true,
+                "from_arc" => extract_variant.from_arc = true,
+                "local" => extract_variant.local = true,
+                "readonly" => extract_variant.readonly = true,
+                "exploded" => extract_variant.exploded = true,
+                "generate_tests" => extract_variant.generate_tests = true,
+                "report_sizes" => extract_variant.report_sizes = true,
+                "skip_unit" => extract_variant.skip_unit = true,
+                "kind" => extract_variant.kind = true,
+                "kind_strings" => extract_variant.kind_strings = true,
+                "kind_index" => extract_variant.kind_index = true,
+                "kind_ord" => extract_variant.kind_ord = true,
+                "kind_key" => extract_variant.kind_key = true,
+                "compare_by_kind" => extract_variant.compare_by_kind = true,
+                "handler" => extract_variant.handler = true,
+                "display" => extract_variant.display = true,
+                "registry" => extract_variant.registry = true,
+                "fixtures" => {
+                    extract_variant.fixtures = Some(if input.peek(token::Paren) {
+                        let inner_content;
+                        parenthesized!(inner_content in input);
+                        let key: Ident = inner_content.parse()?;
+                        if key != "cfg" {
+                            return Err(syn::Error::new(key.span(), "expected `cfg`"));
+                        }
+                        inner_content.parse::<Token![=]>()?;
+                        Some(inner_content.parse()?)
+                    } else {
+                        None
+                    })
+                }
+                "sibling_try_from" => extract_variant.sibling_try_from = true,
+                "match_macro" => extract_variant.match_macro = true,
+                "qualified_debug" => extract_variant.qualified_debug = true,
+                "seal" => extract_variant.seal = true,
+                "strict" => extract_variant.strict = true,
+                "defaults" => extract_variant.defaults = true,
+                "no_doc_inherit" => extract_variant.no_doc_inherit = true,
+                "field_origin_docs" => extract_variant.field_origin_docs = true,
+                "vis_from_fields" => extract_variant.vis_from_fields = true,
+                "clap_args" => extract_variant.clap_args = true,
+                "wrong_variant" => extract_variant.wrong_variant = true,
+                "allow" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant
+                        .allow
+                        .extend(Punctuated::<Path, Token![,]>::parse_terminated(
+                            &inner_content,
+                        )?);
+                }
+                "allow_unused" => extract_variant.allow_unused = true,
+                "inherit_derives" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.inherit_derives = if inner_content.peek(Token![*]) {
+                        inner_content.parse::<Token![*]>()?;
+                        None
+                    } else {
+                        Some(
+                            Punctuated::<Path, Token![,]>::parse_terminated(&inner_content)?
+                                .into_iter()
+                                .collect(),
+                        )
+                    };
+                }
+                "unit_derives" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.unit_derives.extend(
+                        Punctuated::<Path, Token![,]>::parse_terminated(&inner_content)?,
+                    );
+                }
+                "chain" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant
+                        .chain
+                        .extend(Punctuated::<Path, Token![,]>::parse_terminated(
+                            &inner_content,
+                        )?);
+                }
+                "newtype_derives" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.newtype_derives.extend(
+                        Punctuated::<Path, Token![,]>::parse_terminated(&inner_content)?,
+                    );
+                }
+                "accessors" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    let key: Ident = inner_content.parse()?;
+                    if key != "style" {
+                        return Err(syn::Error::new(key.span(), "expected `style`"));
+                    }
+                    inner_content.parse::<Token![=]>()?;
+                    let style: LitStr = inner_content.parse()?;
+                    extract_variant.accessor_style = Some(match style.value().as_str() {
+                        "to" => AccessorStyle::To,
+                        "get" => AccessorStyle::Get,
+                        "plain" => AccessorStyle::Plain,
+                        _ => {
+                            return Err(syn::Error::new(
+                                style.span(),
+                                "expected \"to\", \"get\" or \"plain\"",
+                            ))
+                        }
+                    });
+                }
+                "wrap_fields" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    extract_variant.wrap_fields = Some(inner_content.parse()?);
+                }
+                "assert_macro" => {
+                    extract_variant.assert_macro = Some(if input.peek(token::Paren) {
+                        let inner_content;
+                        parenthesized!(inner_content in input);
+                        Some(inner_content.parse()?)
+                    } else {
+                        None
+                    })
+                }
+                "reserved" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    let policy: Ident = inner_content.parse()?;
+                    extract_variant.reserved = Some(match policy.to_string().as_str() {
+                        "error" => Reserved::Error,
+                        "suffix" => Reserved::Suffix,
+                        "shadow" => Reserved::Shadow,
+                        _ => {
+                            return Err(syn::Error::new(
+                                policy.span(),
+                                "expected `error`, `suffix` or `shadow`",
+                            ))
+                        }
+                    });
+                }
+                "order" => {
+                    let inner_content;
+                    parenthesized!(inner_content in input);
+                    let order: Ident = inner_content.parse()?;
+                    extract_variant.order = Some(match order.to_string().as_str() {
+                        "declaration" => Order::Declaration,
+                        "alphabetical" => Order::Alphabetical,
+                        _ => {
+                            return Err(syn::Error::new(
+                                order.span(),
+                                "expected `declaration` or `alphabetical`",
+                            ))
+                        }
+                    });
+                }
+                "kind_repr" => {
+                    extract_variant.kind_repr = Some(if input.peek(token::Paren) {
+                        let inner_content;
+                        parenthesized!(inner_content in input);
+                        inner_content.parse()?
+                    } else {
+                        Ident::new("u8", ident.span())
+                    })
+                }
+                "indices" => {
+                    extract_variant.indices = Some(if input.peek(token::Paren) {
+                        let inner_content;
+                        parenthesized!(inner_content in input);
+                        let which: Ident = inner_content.parse()?;
+                        if which != "extracted" {
+                            return Err(syn::Error::new(which.span(), "expected `extracted`"));
+                        }
+                        Indices::Extracted
+                    } else {
+                        Indices::All
+                    })
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("invalid parameter name `{}`", ident),
+                    ))
+                }
+            }
+            parse_option_separator(input)?;
+        }
+
+        Ok(extract_variant)
+    }
+}
+
+/// Parses the name of the next option in a list of options, such as those of
+/// `#[extract_variant(...)]`.
+fn parse_option_name(input: ParseStream) -> syn::Result<Ident> {
+    if input.peek(Token![,]) {
+        return Err(input.error("expected an option before the comma"));
+    }
+    input
+        .parse()
+        .map_err(|err| syn::Error::new(err.span(), "expected the name of an option"))
+}
+
+/// Consumes what separates an option from the next one in a list of options: whitespace, or a
+/// comma, which may also trail the list.
+fn parse_option_separator(input: ParseStream) -> syn::Result<()> {
+    if input.parse::<Option<Token![,]>>()?.is_some() && input.peek(Token![,]) {
+        return Err(input.error("expected an option after the comma"));
+    }
+    Ok(())
+}
+
+/// Parses an expression written in an attribute, up to the next comma outside of it, as its tokens.
+/// Only some expressions, not closures for one, can be parsed into a syntax tree without syn's
+/// `full` feature, and they are only pasted into the output anyway.
+fn parse_expr_tokens(input: ParseStream) -> syn::Result<Expr> {
+    let mut tokens = TokenStream::new();
+    // The parameters of a closure are separated by commas too
+    if input.peek(Token![move]) {
+        tokens.extend([input.parse::<TokenTree>()?]);
+    }
+    if input.peek(Token![|]) {
+        tokens.extend([input.parse::<TokenTree>()?]);
+        while !input.peek(Token![|]) {
+            tokens.extend([input.parse::<TokenTree>()?]);
+        }
+        tokens.extend([input.parse::<TokenTree>()?]);
+    }
+    while !input.is_empty() && !input.peek(Token![,]) {
+        tokens.extend([input.parse::<TokenTree>()?]);
+    }
+    if tokens.is_empty() {
+        return Err(input.error("expected an expression"));
+    }
+    Ok(Expr::Verbatim(tokens))
+}
+
+impl Parse for WrapFields {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut wrapper: Type = input.parse()?;
+        // A bare path, such as `Spanned`, takes the type of the field as its only parameter
+        if let Type::Path(TypePath { qself: None, path }) = &wrapper {
+            if path.segments.last().unwrap().arguments.is_none() {
+                wrapper = parse_quote!(#path<_>);
+            }
+        }
+        if !quote! { #wrapper }
+            .into_iter()
+            .any(|token| contains_infer(&token))
+        {
+            return Err(syn::Error::new_spanned(
+                &wrapper,
+                "the wrapper must contain `_` where the type of the field goes, e.g. `Spanned<_>`",
+            ));
+        }
+        let mut wrap = None;
+        let mut unwrap = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            let slot = match key.to_string().as_ref() {
+                "wrap" => &mut wrap,
+                "unwrap" => &mut unwrap,
+                _ => return Err(syn::Error::new(key.span(), "expected `wrap` or `unwrap`")),
+            };
+            input.parse::<Token![=]>()?;
+            *slot = Some(parse_expr_tokens(input)?);
+        }
+        Ok(Self {
+            wrap: wrap.unwrap_or_else(|| parse_quote!(<#wrapper>::from)),
+            unwrap: unwrap.unwrap_or_else(|| parse_quote!(<#wrapper>::into_inner)),
+            wrapper,
+        })
+    }
+}
+
+impl VariantOf {
+    /// Sets the flag named `ident`, returning whether there is one. Flags can be given in place of
+    /// the variant as well as after it, so both places go through this single list.
+    fn set_flag(&mut self, ident: &Ident) -> bool {
+        match ident.to_string().as_str() {
+            "marker_only" => self.marker_only = Some(ident.clone()),
+            "boxed" => self.boxed = true,
+            "transparent" => self.transparent = true,
+            "helpers" => self.helpers = true,
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Parse for VariantOf {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let enum_path = input.parse()?;
+        let mut variant_of = Self {
+            enum_path,
+            variant_ident: None,
+            strict: true,
+            into: None,
+            from: None,
+            marker_only: None,
+            boxed: false,
+            transparent: false,
+            helpers: false,
+            discriminant: None,
+            repr: None,
+        };
+        // The variant comes right after the enum, and the `key = value` options after it
+        if input.peek(Token![,]) && input.peek2(syn::Ident) && !input.peek3(Token![=]) {
+            input.parse::<Token![,]>()?;
+            let ident: Ident = input.parse()?;
+            if !variant_of.set_flag(&ident) {
+                variant_of.variant_ident = Some(ident);
+            }
+        }
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            if variant_of.set_flag(&key) {
+                continue;
+            }
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_ref() {
+                "strict" => variant_of.strict = input.parse::<syn::LitBool>()?.value,
+                "into" => variant_of.into = Some(parse_expr_tokens(input)?),
+                "from" => variant_of.from = Some(parse_expr_tokens(input)?),
+                "discriminant" => variant_of.discriminant = Some(parse_expr_tokens(input)?),
+                "repr" => variant_of.repr = Some(input.parse()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected `strict`, `into`, `from`, `marker_only`, `boxed`, `transparent`, `helpers`, `discriminant` or `repr`",
+                    ))
+                }
+            }
+        }
+        Ok(variant_of)
+    }
+}
+
+impl Parse for EnumBatch {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let shared_attrs = input.call(Attribute::parse_inner)?;
+        let mut enums = Vec::new();
+        while !input.is_empty() {
+            enums.push(input.parse()?);
+        }
+        Ok(Self {
+            shared_attrs,
+            enums,
+        })
+    }
+}
+
+impl Parse for VariantShape {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let struct_path = input.call(Path::parse_mod_style)?;
+        input.parse::<Token![,]>()?;
+        let mut enum_path = input.call(Path::parse_mod_style)?;
+        if enum_path.segments.len() < 2 {
+            return Err(syn::Error::new_spanned(
+                &enum_path,
+                "expected the path of a variant, e.g. `MyEnum::Variant`",
+            ));
+        }
+        let ident = enum_path.segments.pop().unwrap().into_value().ident;
+        enum_path.segments.pop_punct();
+        let fields = if input.peek(token::Brace) {
+            Fields::Named(input.parse()?)
+        } else if input.peek(token::Paren) {
+            Fields::Unnamed(input.parse()?)
+        } else {
+            Fields::Unit
+        };
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self {
+            struct_path,
+            enum_path,
+            variant: Variant {
+                attrs: Vec::new(),
+                ident,
+                fields,
+                discriminant: None,
+            },
+        })
+    }
+}
+
+impl Parse for ForeignEnum {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let enum_path = input.call(Path::parse_mod_style)?;
+
+        let content;
+        braced!(content in input);
+        let mut variants = Punctuated::new();
+        let mut rest = false;
+        while !content.is_empty() {
+            if content.peek(Token![..]) {
+                content.parse::<Token![..]>()?;
+                content.parse::<Option<Token![,]>>()?;
+                if !content.is_empty() {
+                    return Err(content.error("`..` must come after every variant"));
+                }
+                rest = true;
+                break;
+            }
+            variants.push_value(content.parse()?);
+            if content.is_empty() {
+                break;
+            }
+            variants.push_punct(content.parse()?);
+        }
+
+        Ok(Self {
+            attrs,
+            vis,
+            enum_path,
+            variants,
+            rest,
+        })
+    }
+}
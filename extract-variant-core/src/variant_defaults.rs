@@ -0,0 +1,58 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Error, Result};
+
+use crate::{extract_variant::expand, extract_variants::merge_shared_attrs, EnumBatch};
+
+pub fn doit(input: EnumBatch) -> Result<TokenStream> {
+    let EnumBatch {
+        shared_attrs,
+        enums,
+    } = input;
+    if let Some(item) = enums.first() {
+        return Err(Error::new_spanned(
+            &item.ident,
+            "`variant_defaults!` only takes attributes, written as `#![...]`",
+        ));
+    }
+
+    // A `macro_rules!` macro is in scope for the rest of the module, including child modules,
+    // which is where the derives that ask for the defaults can call it
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! __extract_variant_defaults {
+            ($($item:tt)*) => {
+                ::extract_variant::__apply_variant_defaults! { #(#shared_attrs)* $($item)* }
+            };
+        }
+    })
+}
+
+pub fn apply(input: EnumBatch) -> Result<TokenStream> {
+    let EnumBatch {
+        shared_attrs,
+        mut enums,
+    } = input;
+    let (Some(mut item), None) = (enums.pop(), enums.pop()) else {
+        return Err(Error::new(Span::call_site(), "expected a single enum"));
+    };
+
+    // The enum has the last word: a default is left out when the enum has an attribute of the same
+    // name, except for derives and the options, which add up
+    let own: Vec<_> = item
+        .attrs
+        .iter()
+        .map(|attr| attr.path().to_token_stream().to_string())
+        .collect();
+    let defaults: Vec<_> = shared_attrs
+        .into_iter()
+        .filter(|attr| {
+            attr.path().is_ident("derive")
+                || attr.path().is_ident("extract_variant")
+                || !own.contains(&attr.path().to_token_stream().to_string())
+        })
+        .collect();
+    item.attrs = merge_shared_attrs(&defaults, &item.ident, std::mem::take(&mut item.attrs))?;
+    expand(&item, None)
+}
@@ -0,0 +1,357 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    spanned::Spanned, Data, DataStruct, DeriveInput, Error, Expr, Fields, Path, Result, Type,
+    Visibility,
+};
+
+use crate::{
+    cfg_attrs, field_bindings, fields_stream, impl_variant, impl_variant_traits, wildcard_stream,
+    Conversion, VariantOf,
+};
+
+pub fn doit(input: DeriveInput) -> Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(DataStruct { fields, .. }) => fields,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "Variant can only be derived for structs",
+            ))
+        }
+    };
+
+    let variant_of = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("variant_of"))
+        .map(|attr| attr.parse_args::<VariantOf>())
+        .ok_or_else(|| {
+            Error::new(Span::call_site(), "Variant require #[variant_of] attribute")
+        })??;
+
+    let discriminant = match &variant_of.discriminant {
+        Some(discriminant) => {
+            if !matches!(fields, Fields::Unit) {
+                return Err(Error::new_spanned(
+                    discriminant,
+                    "`discriminant` is only available on unit structs, for C-like variants",
+                ));
+            }
+            // Without `strict`, the variant can't be named to check the discriminant against it
+            let checked_variant = variant_of
+                .strict
+                .then(|| variant_of.variant_ident.as_ref().unwrap_or(&input.ident));
+            impl_discriminant(
+                &input.ident,
+                &input.vis,
+                &variant_of.enum_path,
+                checked_variant,
+                discriminant,
+                variant_of.repr.as_ref(),
+            )
+        }
+        None if variant_of.repr.is_some() => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`repr` is the type of the `discriminant`, which is missing",
+            ))
+        }
+        None => TokenStream::new(),
+    };
+    let helpers = if variant_of.helpers {
+        impl_helpers(&input.ident, &input.vis, fields, &variant_of)?
+    } else {
+        TokenStream::new()
+    };
+    let conversions = impl_conversions(&input.ident, fields, variant_of)?;
+    Ok(quote! {
+        #conversions
+        #discriminant
+        #helpers
+    })
+}
+
+/// Implements the conversions and traits asked for by the `#[variant_of(...)]` attribute.
+fn impl_conversions(
+    struct_ident: &Ident,
+    fields: &Fields,
+    variant_of: VariantOf,
+) -> Result<TokenStream> {
+    let VariantOf {
+        enum_path,
+        variant_ident,
+        strict,
+        into,
+        from,
+        marker_only,
+        boxed,
+        transparent,
+        ..
+    } = variant_of;
+
+    if let Some(marker_only) = marker_only {
+        if !strict || into.is_some() || from.is_some() || boxed || transparent {
+            return Err(Error::new(
+                marker_only.span(),
+                "`marker_only` relies on hand-written conversions, which rules out `strict`, `into`, `from`, `boxed` and `transparent`",
+            ));
+        }
+        return Ok(impl_marker(struct_ident, &enum_path, &marker_only));
+    }
+    if !strict {
+        let (Some(into), Some(from)) = (into, from) else {
+            return Err(Error::new(
+                Span::call_site(),
+                "`strict = false` requires both `into` and `from` functions",
+            ));
+        };
+        if variant_ident.is_some() || boxed || transparent {
+            return Err(Error::new(
+                Span::call_site(),
+                "`strict = false` converts through `into` and `from`, which makes the variant, `boxed` and `transparent` unused",
+            ));
+        }
+        return Ok(impl_custom_variant(struct_ident, &enum_path, &into, &from));
+    }
+    if into.is_some() || from.is_some() {
+        return Err(Error::new(
+            Span::call_site(),
+            "`into` and `from` are only used with `strict = false`",
+        ));
+    }
+
+    if boxed && transparent {
+        return Err(Error::new(
+            Span::call_site(),
+            "`boxed` puts the struct itself in the variant, while `transparent` only puts its field",
+        ));
+    }
+    if boxed {
+        let variant_ident = variant_ident.as_ref().unwrap_or(struct_ident);
+        return Ok(impl_boxed_variant(struct_ident, &enum_path, variant_ident));
+    }
+    if transparent {
+        let variant_ident = variant_ident.as_ref().unwrap_or(struct_ident);
+        return impl_transparent_variant(struct_ident, fields, &enum_path, variant_ident);
+    }
+
+    Ok(impl_variant(
+        struct_ident,
+        &Conversion::identity(fields),
+        &enum_path,
+        variant_ident.as_ref(),
+        false,
+    ))
+}
+
+/// Implements the conversions of a struct by calling `into` and `from`, for variants that can't be
+/// named reliably, e.g. hidden or renamed ones of a `#[non_exhaustive]` enum from another crate.
+fn impl_custom_variant(
+    struct_ident: &Ident,
+    enum_path: &Path,
+    into: &Expr,
+    from: &Expr,
+) -> TokenStream {
+    let struct_path = Path::from(struct_ident.clone());
+    let traits = impl_variant_traits(&struct_path, enum_path, false);
+    quote! {
+        impl ::std::convert::From<#struct_path> for #enum_path {
+            fn from(value: #struct_path) -> Self {
+                (#into)(value)
+            }
+        }
+        impl ::std::convert::TryFrom<#enum_path> for #struct_path {
+            type Error = #enum_path;
+            fn try_from(value: #enum_path) -> ::std::result::Result<Self, Self::Error> {
+                (#from)(value)
+            }
+        }
+        #traits
+    }
+}
+
+/// Implements the conversions of a struct held in a `Box` by a single-field tuple variant, boxing
+/// and unboxing it on the way.
+fn impl_boxed_variant(
+    struct_ident: &Ident,
+    enum_path: &Path,
+    variant_ident: &Ident,
+) -> TokenStream {
+    let struct_path = Path::from(struct_ident.clone());
+    let traits = impl_variant_traits(&struct_path, enum_path, false);
+    quote! {
+        impl ::std::convert::From<#struct_path> for #enum_path {
+            fn from(value: #struct_path) -> Self {
+                Self::#variant_ident(::std::boxed::Box::new(value))
+            }
+        }
+        impl ::std::convert::TryFrom<#enum_path> for #struct_path {
+            type Error = #enum_path;
+            fn try_from(value: #enum_path) -> ::std::result::Result<Self, Self::Error> {
+                if let #enum_path::#variant_ident(boxed) = value { Ok(*boxed) } else { Err(value) }
+            }
+        }
+        #traits
+    }
+}
+
+/// Implements the conversions of a single-field struct whose field converts into and from the
+/// single field of a tuple variant with [Into], e.g. a wrapper around the type of the variant's
+/// field.
+fn impl_transparent_variant(
+    struct_ident: &Ident,
+    fields: &Fields,
+    enum_path: &Path,
+    variant_ident: &Ident,
+) -> Result<TokenStream> {
+    if fields.len() != 1 {
+        return Err(Error::new(
+            Span::call_site(),
+            "`transparent` forwards the conversions to the only field of the struct, which must have exactly one",
+        ));
+    }
+    let member = fields.members().next().unwrap();
+    let struct_path = Path::from(struct_ident.clone());
+    let traits = impl_variant_traits(&struct_path, enum_path, false);
+    Ok(quote! {
+        impl ::std::convert::From<#struct_path> for #enum_path {
+            fn from(value: #struct_path) -> Self {
+                Self::#variant_ident(::std::convert::Into::into(value.#member))
+            }
+        }
+        impl ::std::convert::TryFrom<#enum_path> for #struct_path {
+            type Error = #enum_path;
+            fn try_from(value: #enum_path) -> ::std::result::Result<Self, Self::Error> {
+                if let #enum_path::#variant_ident(field) = value {
+                    Ok(Self { #member: ::std::convert::Into::into(field) })
+                } else {
+                    Err(value)
+                }
+            }
+        }
+        #traits
+    })
+}
+
+/// Implements `matches` and `extract_ref` on the struct, telling whether the enum holds its variant
+/// and borrowing what the variant holds, for enums from other crates that can't get inherent
+/// methods. The variant has the fields of the struct, or holds the struct itself with `boxed`, in
+/// which case `extract_ref` borrows the struct. With `transparent`, the type of the variant's field
+/// is unknown, so only `matches` is implemented.
+fn impl_helpers(
+    struct_ident: &Ident,
+    vis: &Visibility,
+    fields: &Fields,
+    variant_of: &VariantOf,
+) -> Result<TokenStream> {
+    if !variant_of.strict || variant_of.marker_only.is_some() {
+        return Err(Error::new(
+            Span::call_site(),
+            "`helpers` matches the variant by its shape, which `strict = false` and `marker_only` leave unknown",
+        ));
+    }
+    if let Some(field) = fields
+        .iter()
+        .find(|field| cfg_attrs(field).next().is_some())
+    {
+        return Err(Error::new_spanned(
+            field,
+            "`helpers` can't borrow fields that are behind `#[cfg]`",
+        ));
+    }
+    let enum_path = &variant_of.enum_path;
+    let variant_ident = variant_of.variant_ident.as_ref().unwrap_or(struct_ident);
+    let (pattern, extract_ref) = if variant_of.boxed {
+        let extract_ref = quote! {
+            /// Borrows the struct held by the variant, or returns [`None`] if `value` is another
+            /// variant.
+            #vis fn extract_ref(value: &#enum_path) -> ::std::option::Option<&Self> {
+                #[allow(unreachable_patterns)]
+                match value {
+                    #enum_path::#variant_ident(boxed) => ::std::option::Option::Some(&**boxed),
+                    _ => ::std::option::Option::None,
+                }
+            }
+        };
+        (quote! { (..) }, Some(extract_ref))
+    } else if variant_of.transparent {
+        (quote! { (..) }, None)
+    } else if fields.is_empty() {
+        (wildcard_stream(fields), None)
+    } else {
+        let pattern = fields_stream(fields);
+        let bindings = field_bindings(fields);
+        let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+        let (refs, value) = if let [ty] = types.as_slice() {
+            (quote! { &#ty }, quote! { #(#bindings)* })
+        } else {
+            (quote! { (#(&#types,)*) }, quote! { (#(#bindings,)*) })
+        };
+        let extract_ref = quote! {
+            /// Borrows the fields of the variant, or returns [`None`] if `value` is another variant.
+            #vis fn extract_ref(value: &#enum_path) -> ::std::option::Option<#refs> {
+                #[allow(unreachable_patterns)]
+                match value {
+                    #enum_path::#variant_ident #pattern => ::std::option::Option::Some(#value),
+                    _ => ::std::option::Option::None,
+                }
+            }
+        };
+        (wildcard_stream(fields), Some(extract_ref))
+    };
+    Ok(quote! {
+        impl #struct_ident {
+            /// Returns whether `value` is the variant of this struct.
+            #vis fn matches(value: &#enum_path) -> bool {
+                ::std::matches!(value, #enum_path::#variant_ident #pattern)
+            }
+            #extract_ref
+        }
+    })
+}
+
+/// Implements a `DISCRIMINANT` constant on a unit struct, with the discriminant of its C-like
+/// variant, and checks at compile time that the variant does have this discriminant.
+fn impl_discriminant(
+    struct_ident: &Ident,
+    vis: &Visibility,
+    enum_path: &Path,
+    variant_ident: Option<&Ident>,
+    discriminant: &Expr,
+    repr: Option<&Type>,
+) -> TokenStream {
+    let repr = match repr {
+        Some(repr) => quote! { #repr },
+        None => quote! { isize },
+    };
+    let check = variant_ident.map(|variant_ident| {
+        let message = format!(
+            "`DISCRIMINANT` of `{}` differs from the discriminant of its variant",
+            struct_ident
+        );
+        // Spanned on the discriminant, so that a mismatch is reported on it
+        quote_spanned! {discriminant.span()=>
+            const _: () = ::std::assert!(
+                #enum_path::#variant_ident as #repr == #struct_ident::DISCRIMINANT,
+                #message
+            );
+        }
+    });
+    quote! {
+        impl #struct_ident {
+            /// The discriminant of the variant of the enum.
+            #vis const DISCRIMINANT: #repr = #discriminant;
+        }
+        #check
+    }
+}
+
+/// Only implements `Variant` on a struct whose conversions are written by hand. The impl is spanned
+/// on `marker_only`, so that a missing conversion is reported on the attribute asking for it.
+fn impl_marker(struct_ident: &Ident, enum_path: &Path, marker_only: &Ident) -> TokenStream {
+    let span = marker_only.span();
+    quote_spanned! {span=>
+        impl ::variant_traits::Variant<#enum_path> for #struct_ident {}
+    }
+}
@@ -0,0 +1,1125 @@
+//! One test per attribute the macros understand, checking the items they expand to.
+
+use proc_macro2::{Delimiter, Group, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{File, Item, ItemStruct};
+
+fn expand(input: TokenStream) -> File {
+    let expanded = extract_variant_core::extract_variant(input);
+    assert!(
+        !expanded.to_string().contains("compile_error"),
+        "unexpected error: {}",
+        expanded
+    );
+    syn::parse2(expanded).unwrap()
+}
+
+fn expand_error(input: TokenStream) -> String {
+    let expanded = extract_variant_core::extract_variant(input).to_string();
+    assert!(
+        expanded.contains("compile_error"),
+        "expected an error: {}",
+        expanded
+    );
+    expanded
+}
+
+fn find_struct<'a>(file: &'a File, name: &str) -> &'a ItemStruct {
+    file.items
+        .iter()
+        .find_map(|item| match item {
+            Item::Struct(item) if item.ident == name => Some(item),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no struct `{}`", name))
+}
+
+fn has_struct(file: &File, name: &str) -> bool {
+    file.items
+        .iter()
+        .any(|item| matches!(item, Item::Struct(item) if item.ident == name))
+}
+
+fn attrs(item: &ItemStruct) -> Vec<String> {
+    item.attrs
+        .iter()
+        .map(|attr| attr.to_token_stream().to_string())
+        .collect()
+}
+
+fn field_types(item: &ItemStruct) -> Vec<String> {
+    item.fields
+        .iter()
+        .map(|field| field.ty.to_token_stream().to_string())
+        .collect()
+}
+
+fn has_impl_of(file: &File, trait_path: TokenStream) -> bool {
+    let trait_path = trait_path.to_string();
+    file.items.iter().any(|item| match item {
+        Item::Impl(item) => item
+            .trait_
+            .as_ref()
+            .is_some_and(|(_, path, _)| path.to_token_stream().to_string() == trait_path),
+        _ => false,
+    })
+}
+
+#[test]
+fn prefix_and_suffix() {
+    for input in [
+        quote! {
+            #[prefix(Pre)]
+            #[suffix(Post)]
+            enum MyEnum { Variant }
+        },
+        quote! {
+            #[extract_variant(prefix(Pre) suffix(Post))]
+            enum MyEnum { Variant }
+        },
+    ] {
+        let file = expand(input);
+        assert!(has_struct(&file, "PreVariantPost"));
+        assert!(!has_struct(&file, "Variant"));
+    }
+    expand_error(quote! {
+        #[prefix(Pre)]
+        #[extract_variant(prefix(Other))]
+        enum MyEnum { Variant }
+    });
+}
+
+#[test]
+fn no_impl() {
+    for input in [
+        quote! {
+            #[no_impl]
+            enum MyEnum { Variant }
+        },
+        quote! {
+            #[extract_variant(no_impl)]
+            enum MyEnum { Variant }
+        },
+    ] {
+        let file = expand(input);
+        assert!(has_struct(&file, "Variant"));
+        assert!(!has_impl_of(&file, quote!(::std::convert::From<Variant>)));
+    }
+}
+
+#[test]
+fn bare_extract_variant() {
+    let file = expand(quote! {
+        #[extract_variant]
+        enum MyEnum { Variant }
+    });
+    assert!(has_struct(&file, "Variant"));
+}
+
+#[test]
+fn unknown_option() {
+    let error = expand_error(quote! {
+        #[extract_variant(no_such_option)]
+        enum MyEnum { Variant }
+    });
+    assert!(error.contains("invalid parameter name"));
+}
+
+#[test]
+fn variant_attrs() {
+    let file = expand(quote! {
+        enum MyEnum {
+            #[variant_attrs(#[derive(Clone)], repr(C))]
+            Variant(u8),
+        }
+    });
+    assert_eq!(
+        attrs(find_struct(&file, "Variant"))[..2],
+        ["# [derive (Clone)]", "# [repr (C)]"]
+    );
+}
+
+#[test]
+fn impl_attrs() {
+    let file = expand(quote! {
+        enum MyEnum {
+            #[impl_attrs(#[cfg(feature = "conversions")], allow(unused))]
+            Variant(u8),
+        }
+    });
+    assert!(!attrs(find_struct(&file, "Variant"))
+        .iter()
+        .any(|attr| attr.contains("cfg")));
+    let impls: Vec<_> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Impl(item) if item.self_ty.to_token_stream().to_string() != "MyEnum" => {
+                Some(item)
+            }
+            _ => None,
+        })
+        .collect();
+    assert!(!impls.is_empty());
+    for item in impls {
+        let attrs: Vec<_> = item
+            .attrs
+            .iter()
+            .map(|attr| attr.to_token_stream().to_string())
+            .collect();
+        assert_eq!(
+            attrs,
+            ["# [cfg (feature = \"conversions\")]", "# [allow (unused)]"]
+        );
+    }
+}
+
+#[test]
+fn fallible_from() {
+    let file = expand(quote! {
+        enum MyEnum {
+            #[fallible_from(validator = check, error = String)]
+            Variant(u8),
+        }
+    });
+    assert!(!has_impl_of(&file, quote!(::std::convert::From<Variant>)));
+    assert!(has_impl_of(&file, quote!(::std::convert::TryFrom<Variant>)));
+    assert!(has_impl_of(&file, quote!(::std::convert::TryFrom<MyEnum>)));
+    let error = expand_error(quote! {
+        #[extract_variant(exploded)]
+        enum MyEnum {
+            #[fallible_from(validator = check, error = String)]
+            Variant(u8),
+        }
+    });
+    assert!(error.contains("exploded"));
+    expand_error(quote! {
+        enum MyEnum {
+            #[fallible_from(validator = check)]
+            Variant(u8),
+        }
+    });
+}
+
+#[test]
+fn variant_derive_and_inherited_derives() {
+    let file = expand(quote! {
+        #[derive(Debug)]
+        enum MyEnum {
+            #[variant_derive(Debug, Hash)]
+            Variant(u8),
+        }
+    });
+    let attrs = attrs(find_struct(&file, "Variant"));
+    assert!(attrs.contains(&"# [derive (Debug)]".to_owned()));
+    assert!(attrs.contains(&"# [derive (Hash)]".to_owned()));
+}
+
+#[test]
+fn newtype_derives() {
+    let file = expand(quote! {
+        #[extract_variant(newtype_derives(Copy, Hash))]
+        enum MyEnum {
+            Newtype(u8),
+            Pair(u8, u8),
+            Named { field: u8 },
+        }
+    });
+    assert_eq!(
+        attrs(find_struct(&file, "Newtype"))[0],
+        "# [derive (Copy , Hash)]"
+    );
+    for name in ["Pair", "Named"] {
+        assert!(!attrs(find_struct(&file, name))
+            .iter()
+            .any(|attr| attr.contains("derive")));
+    }
+}
+
+#[test]
+fn docs_are_inherited() {
+    let file = expand(quote! {
+        enum MyEnum {
+            /// Kept.
+            Kept,
+            /// Dropped.
+            #[no_doc_inherit]
+            Dropped,
+        }
+    });
+    assert!(attrs(find_struct(&file, "Kept"))
+        .iter()
+        .any(|attr| attr.contains("Kept.")));
+    assert!(!attrs(find_struct(&file, "Dropped"))
+        .iter()
+        .any(|attr| attr.contains("Dropped.")));
+}
+
+#[test]
+fn doc_links_to_self_are_rewritten() {
+    let file = expand(quote! {
+        #[prefix(My)]
+        enum MyEnum {
+            /// See [Self::Other], [`Self::Excluded`] and [Self::new()], not SelfContained.
+            Variant {
+                /// The [`Self`] it belongs to.
+                field: u8,
+            },
+            Other,
+            #[exclude]
+            Excluded,
+        }
+    });
+    let variant = find_struct(&file, "MyVariant");
+    assert!(attrs(variant).iter().any(|attr| attr
+        .contains("See [MyOther], [`MyEnum::Excluded`] and [MyEnum::new()], not SelfContained.")));
+    let field = variant.fields.iter().next().unwrap();
+    assert!(field.attrs.iter().any(|attr| quote!(#attr)
+        .to_string()
+        .contains("The [`MyEnum`] it belongs to.")));
+}
+
+#[test]
+fn conversion_attributes_take_full_types_and_paths() {
+    let file = expand(quote! {
+        #[extract_variant(wrap_fields(
+            std::sync::Arc<Spanned<_, Span>>,
+            unwrap = my_mod::unwrap::<Span>,
+        ))]
+        enum MyEnum {
+            #[map_type(0: Vec<Expr> => Vec<Spanned<Expr>>, 1: u8 => <u8 as Wide>::Output)]
+            Variant(Vec<Expr>, u8),
+        }
+    });
+    assert_eq!(
+        field_types(find_struct(&file, "Variant")),
+        [
+            "std :: sync :: Arc < Spanned < Vec < Spanned < Expr > > , Span > >",
+            "std :: sync :: Arc < Spanned < < u8 as Wide > :: Output , Span > >",
+        ]
+    );
+    let output = extract_variant_core::extract_variant(quote! {
+        #[extract_variant(wrap_fields(
+            std::sync::Arc<Spanned<_, Span>>,
+            unwrap = my_mod::unwrap::<Span>,
+        ))]
+        enum MyEnum {
+            Variant(u8),
+        }
+    })
+    .to_string();
+    assert!(output.contains("< std :: sync :: Arc < Spanned < _ , Span > > > :: from"));
+    assert!(output.contains("my_mod :: unwrap :: < Span >"));
+    let error = expand_error(quote! {
+        #[extract_variant(wrap_fields(Spanned<u8>))]
+        enum MyEnum {
+            Variant(u8),
+        }
+    });
+    assert!(error.contains("must contain `_`"));
+}
+
+#[test]
+fn field_docs_survive_field_transformations() {
+    let file = expand(quote! {
+        #[extract_variant(field_origin_docs wrap_fields(Spanned))]
+        enum MyEnum {
+            #[tuple]
+            #[map_type(second: u8 => u32)]
+            Variant {
+                /// The first field.
+                #[boxed]
+                first: u8,
+                second: u8,
+            },
+        }
+    });
+    let docs: Vec<Vec<String>> = find_struct(&file, "Variant")
+        .fields
+        .iter()
+        .map(|field| {
+            field
+                .attrs
+                .iter()
+                .map(|attr| attr.to_token_stream().to_string())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        docs,
+        [
+            vec![
+                "# [doc = r\" The first field.\"]".to_owned(),
+                "# [doc = \"\"]".to_owned(),
+                "# [doc = \" Corresponds to `MyEnum::Variant.first`.\"]".to_owned(),
+            ],
+            vec!["# [doc = \" Corresponds to `MyEnum::Variant.second`.\"]".to_owned()],
+        ]
+    );
+}
+
+#[test]
+fn vis() {
+    let file = expand(quote! {
+        #[extract_variant(vis_from_fields)]
+        pub enum MyEnum {
+            Variant {
+                #[vis(pub(crate))]
+                restricted: u8,
+                public: u8,
+            },
+        }
+    });
+    let variant = find_struct(&file, "Variant");
+    assert_eq!(variant.vis.to_token_stream().to_string(), "pub (crate)");
+    let visibilities: Vec<String> = variant
+        .fields
+        .iter()
+        .map(|field| field.vis.to_token_stream().to_string())
+        .collect();
+    assert_eq!(visibilities, ["pub (crate)", "pub"]);
+    assert!(variant.fields.iter().all(|field| field.attrs.is_empty()));
+}
+
+#[test]
+fn local() {
+    expand(quote! {
+        #[extract_variant(local kind)]
+        enum MyEnum { Variant(u8) }
+    });
+    for option in [quote!(hygienic), quote!(generate_tests)] {
+        let error = expand_error(quote! {
+            #[extract_variant(local #option)]
+            enum MyEnum { Variant(u8) }
+        });
+        assert!(error.contains("function body"));
+    }
+}
+
+#[test]
+fn pub_in() {
+    let file = expand(quote! {
+        #[extract_variant(pub_in(crate::model))]
+        pub enum MyEnum { Variant(u8) }
+    });
+    let variant = find_struct(&file, "Variant");
+    assert_eq!(
+        variant.vis.to_token_stream().to_string(),
+        "pub (in crate :: model)"
+    );
+    let error = expand_error(quote! {
+        #[extract_variant(pub_in(model))]
+        pub enum MyEnum { Variant(u8) }
+    });
+    assert!(error.contains("ancestor module"));
+}
+
+#[test]
+fn exclude() {
+    let file = expand(quote! {
+        enum MyEnum {
+            #[exclude]
+            Excluded,
+            Extracted,
+        }
+    });
+    assert!(!has_struct(&file, "Excluded"));
+    assert!(has_struct(&file, "Extracted"));
+    let output = expand(quote! {
+        #[extract_variant(kind)]
+        enum MyEnum {
+            #[exclude(struct_only)]
+            Excluded,
+            #[exclude(all)]
+            Hidden,
+            Extracted,
+        }
+    })
+    .to_token_stream()
+    .to_string();
+    assert!(output.contains("enum MyEnumKind { Excluded , Extracted = 2 }"));
+    assert!(output.contains("Self :: Hidden => :: std :: option :: Option :: None"));
+    expand_error(quote! {
+        enum MyEnum {
+            #[exclude(everything)]
+            Variant,
+        }
+    });
+}
+
+#[test]
+fn variant_attrs_companions() {
+    let file = expand(quote! {
+        #[extract_variant(kind exploded)]
+        enum MyEnum {
+            #[variant_attrs(kind: #[serde(rename = "unit")], exploded: doc = "Unit", derive(Hash))]
+            UnitVariant,
+        }
+    });
+    let variant_attrs = |name: &str| {
+        file.items
+            .iter()
+            .find_map(|item| match item {
+                Item::Enum(item) if item.ident == name => Some(&item.variants[0].attrs),
+                _ => None,
+            })
+            .unwrap()
+            .iter()
+            .map(|attr| attr.to_token_stream().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        variant_attrs("MyEnumKind"),
+        ["# [serde (rename = \"unit\")]"]
+    );
+    assert_eq!(variant_attrs("MyEnumExploded"), ["# [doc = \"Unit\"]"]);
+    assert!(attrs(find_struct(&file, "UnitVariant")).contains(&"# [derive (Hash)]".to_owned()));
+    let error = expand_error(quote! {
+        enum MyEnum {
+            #[variant_attrs(ref: derive(Clone))]
+            UnitVariant,
+        }
+    });
+    assert!(error.contains("no `ref` companion"));
+}
+
+#[test]
+fn variant_attrs_content_round_trips() {
+    let metas = [
+        quote!(doc = "brackets ] and [ in a doc"),
+        quote!(doc = r"a ]] raw string"),
+        quote!(rustfmt::skip),
+        quote!(serde::rename = "x"),
+        quote!(cfg_attr(test, derive(Default))),
+    ];
+    for meta in metas {
+        let expected = quote!(#[#meta]).to_string();
+        // Macros wrap their fragments in invisible groups, e.g. `$meta:meta`
+        let group = Group::new(Delimiter::None, meta.clone());
+        for content in [
+            quote!(#[#meta]),
+            quote!(#meta),
+            quote!(owned: #meta,),
+            quote!(#group),
+        ] {
+            for attr in [
+                quote!(#[variant_attrs(#content)]),
+                quote!(#[extract_variant(variant_attrs(#content))]),
+            ] {
+                let file = expand(quote! {
+                    enum MyEnum {
+                        #attr
+                        Variant(u8),
+                    }
+                });
+                assert_eq!(attrs(find_struct(&file, "Variant"))[0], expected);
+            }
+        }
+    }
+    let file = expand(quote! {
+        enum MyEnum {
+            #[variant_attrs(
+                /// A doc comment with ] in it
+                #[derive(Clone)]
+            )]
+            Variant(u8),
+        }
+    });
+    let attrs = attrs(find_struct(&file, "Variant"));
+    assert!(attrs[0].contains("A doc comment with ] in it"));
+    assert_eq!(attrs[1], "# [derive (Clone)]");
+}
+
+#[test]
+fn exclude_keep_impls() {
+    let input = |options: TokenStream| {
+        quote! {
+            #[extract_variant(#options)]
+            pub enum MyEnum {
+                #[exclude(keep_impls)]
+                Declared(u8),
+                Generated(u8),
+            }
+        }
+    };
+    let file = expand(input(quote!(kind)));
+    assert!(!has_struct(&file, "Declared"));
+    assert!(has_struct(&file, "Generated"));
+    assert!(has_impl_of(
+        &file,
+        quote!(::variant_traits::Variant<MyEnum>)
+    ));
+    let output = extract_variant_core::extract_variant(input(quote!(hygienic))).to_string();
+    assert!(output.contains("for Declared"));
+    assert!(output.contains("pub use __extract_variant_MyEnum :: { Generated }"));
+    let error = expand_error(quote! {
+        enum MyEnum {
+            #[exclude(keep)]
+            Declared(u8),
+        }
+    });
+    assert!(error.contains("expected `struct_only`, `all`, `kind_only` or `keep_impls`"));
+}
+
+#[test]
+fn conflicts() {
+    let error = expand_error(quote! {
+        #[extract_variant(chain(serde_json::Value::Inner) dynamic(serde_json::Value))]
+        enum MyEnum {
+            Variant(u8),
+        }
+    });
+    assert!(error.contains(
+        "`From<Variant>` for `serde_json::Value` would be implemented twice, by `chain(serde_json :: Value :: Inner)` and by `dynamic(serde_json :: Value)`"
+    ));
+    let error = expand_error(quote! {
+        #[extract_variant(chain(Outer::First, Outer::Second))]
+        enum MyEnum {
+            Variant(u8),
+        }
+    });
+    assert!(error.contains("would be implemented twice"));
+    let error = expand_error(quote! {
+        #[extract_variant(trim_prefix(Http))]
+        enum Method {
+            HttpGet,
+            Get(u8),
+        }
+    });
+    assert!(error.contains(
+        "`Get` would be declared twice, as the struct of `HttpGet` and as the struct of `Get`"
+    ));
+    let error = expand_error(quote! {
+        #[extract_variant(kind)]
+        enum MyEnum {
+            MyEnumKind,
+        }
+    });
+    assert!(error.contains("as the type generated by `kind` and as the struct of `MyEnumKind`"));
+    let error = expand_error(quote! {
+        enum Wrapper {
+            Wrapper(u8),
+        }
+    });
+    assert!(error.contains("as the enum and as the struct of `Wrapper`"));
+}
+
+#[test]
+fn reserved() {
+    let input = |policy: TokenStream| {
+        quote! {
+            #[extract_variant(#policy)]
+            enum Value {
+                String(String),
+                Number(f64),
+            }
+        }
+    };
+    let file = expand(input(quote!(reserved(suffix))));
+    assert!(has_struct(&file, "StringVariant"));
+    assert!(has_struct(&file, "Number"));
+    let file = expand(input(quote!(reserved(shadow))));
+    assert!(has_struct(&file, "String"));
+    let error = expand_error(input(quote!(reserved(error))));
+    assert!(error.contains("would shadow `String` from the prelude"));
+}
+
+#[test]
+fn strict() {
+    let input = |options: TokenStream| {
+        quote! {
+            #[extract_variant(#options)]
+            enum MyEnum {
+                #[exclude]
+                #[variant_derive(Default)]
+                Excluded,
+                Variant(u8),
+            }
+        }
+    };
+    assert!(has_struct(&expand(input(quote!(kind))), "Variant"));
+    let error = expand_error(input(quote!(strict)));
+    assert!(error.contains("`#[variant_derive]` configures the struct of the variant"));
+    let error = expand_error(quote! {
+        #[extract_variant(strict skip_unit)]
+        enum MyEnum {
+            #[variant_attrs(derive(Default))]
+            Unit,
+            #[suffix(Struct)]
+            Variant(u8),
+        }
+    });
+    assert!(error.contains("as `skip_unit` doesn't extract this variant"));
+    let error = expand_error(quote! {
+        #[extract_variant(strict)]
+        #[exclude]
+        enum MyEnum {
+            Variant(u8),
+        }
+    });
+    assert!(error.contains("does nothing on the enum"));
+    let error = expand_error(quote! {
+        #[extract_variant(strict kind)]
+        enum MyEnum {
+            #[exclude(all)]
+            #[kind_name(Other)]
+            Hidden,
+            #[suffix(Struct)]
+            Variant(u8),
+        }
+    });
+    assert!(error.contains("`#[kind_name]` configures what `#[exclude(all)]`"));
+}
+
+#[test]
+fn namespaced_helpers() {
+    let file = expand(quote! {
+        enum MyEnum {
+            #[extract_variant(exclude)]
+            Excluded,
+            #[extract_variant(tuple variant_attrs(repr(C)))]
+            #[variant_attrs(repr(u8))]
+            Variant {
+                #[extract_variant(boxed)]
+                first: u8,
+            },
+        }
+    });
+    assert!(!has_struct(&file, "Excluded"));
+    let variant = find_struct(&file, "Variant");
+    assert_eq!(attrs(variant)[0], "# [repr (C)]");
+    assert_eq!(field_types(variant), [":: std :: boxed :: Box < u8 >"]);
+    assert!(variant.fields.iter().all(|field| field.ident.is_none()));
+    let error = expand_error(quote! {
+        enum MyEnum {
+            #[extract_variant(prefix(Pre))]
+            Variant,
+        }
+    });
+    assert!(error.contains("invalid parameter name"));
+}
+
+#[test]
+fn boxed_and_unboxed() {
+    let file = expand(quote! {
+        enum MyEnum {
+            Variant(#[boxed] u8, #[unboxed] Box<u16>),
+        }
+    });
+    let variant = find_struct(&file, "Variant");
+    assert_eq!(
+        field_types(variant),
+        [":: std :: boxed :: Box < u8 >", "u16"]
+    );
+    assert!(variant.fields.iter().all(|field| field.attrs.is_empty()));
+    expand_error(quote! {
+        enum MyEnum {
+            Variant(#[unboxed] u8),
+        }
+    });
+}
+
+#[test]
+fn map_type_and_tuple() {
+    let file = expand(quote! {
+        enum MyEnum {
+            #[tuple]
+            #[map_type(second: u8 => u32)]
+            Variant { first: String, second: u8 },
+        }
+    });
+    let variant = find_struct(&file, "Variant");
+    assert!(variant.fields.iter().all(|field| field.ident.is_none()));
+    assert_eq!(field_types(variant), ["String", "u32"]);
+    let error = expand_error(quote! {
+        enum MyEnum {
+            #[map_type(third: u8 => u32)]
+            Variant { first: u8 },
+        }
+    });
+    assert!(error.contains("no such field"));
+}
+
+#[test]
+fn codes_and_tags() {
+    let output = expand(quote! {
+        enum MyEnum {
+            #[code(404)]
+            #[tag(1)]
+            Variant,
+        }
+    })
+    .to_token_stream()
+    .to_string();
+    assert!(output.contains("const CODE : u16 = 404"));
+    assert!(output.contains("const TAG : u8 = 1"));
+}
+
+#[test]
+fn sample() {
+    let output = expand(quote! {
+        #[extract_variant(fixtures)]
+        enum MyEnum {
+            #[sample(field = 7)]
+            Variant { field: u8 },
+        }
+    })
+    .to_token_stream()
+    .to_string();
+    assert!(output.contains("fn sample ()"));
+    assert!(output.contains("7"));
+}
+
+#[test]
+fn kind_name() {
+    let output = expand(quote! {
+        #[extract_variant(kind kind_strings)]
+        enum MyEnum {
+            #[kind_name("renamed")]
+            Variant,
+        }
+    })
+    .to_token_stream()
+    .to_string();
+    assert!(output.contains("\"renamed\""));
+}
+
+#[test]
+fn accessor_name() {
+    let output = expand(quote! {
+        #[extract_variant(to_getters)]
+        enum MyEnum {
+            #[accessor_name(custom)]
+            Variant(u8),
+        }
+    })
+    .to_token_stream()
+    .to_string();
+    assert!(output.contains("fn to_custom"));
+}
+
+#[test]
+fn seal() {
+    let output = extract_variant_core::extract_variant(quote! {
+        #[extract_variant(seal)]
+        pub enum MyEnum {
+            Plain(u8),
+            #[fallible_from(validator = check, error = String)]
+            Checked(u8),
+            #[exclude]
+            Excluded,
+        }
+    })
+    .to_string();
+    assert!(output.contains("impl MyEnumVariant for Plain"));
+    assert!(!output.contains("impl MyEnumVariant for Checked"));
+    let output = extract_variant_core::extract_variant(quote! {
+        #[extract_variant(seal sibling_try_from)]
+        pub enum MyEnum { First(u8), Second(u8) }
+    })
+    .to_string();
+    assert!(output.contains("impl :: std :: convert :: TryFrom < First > for Second"));
+    assert!(!output.contains("TryFrom < MyEnumVariant >"));
+    assert!(!output.contains("for MyEnumVariant {"));
+    let error = expand_error(quote! {
+        #[extract_variant(seal no_impl)]
+        pub enum MyEnum { Plain(u8) }
+    });
+    assert!(error.contains("`seal`"));
+}
+
+#[test]
+fn assert_macro() {
+    let local = extract_variant_core::extract_variant(quote! {
+        #[extract_variant(assert_macro)]
+        pub enum Shape { Circle(f64) }
+    })
+    .to_string();
+    assert!(local.contains("macro_rules ! assert_shape_variants"));
+    assert!(local.contains("Shape :: $ variant { .. }"));
+    let exported = extract_variant_core::extract_variant(quote! {
+        #[extract_variant(assert_macro(crate::shapes::Shape))]
+        pub enum Shape { Circle(f64) }
+    })
+    .to_string();
+    assert!(exported.contains("# [macro_export]"));
+    assert!(exported.contains("$ crate :: shapes :: Shape :: $ variant { .. }"));
+    let error = expand_error(quote! {
+        #[extract_variant(assert_macro(shapes::Shape))]
+        pub enum Shape { Circle(f64) }
+    });
+    assert!(error.contains("starting with `crate`"));
+}
+
+#[test]
+fn variant_of() {
+    let output = extract_variant_core::derive_variant(quote! {
+        #[variant_of(MyEnum, Other)]
+        struct Variant(u8);
+    })
+    .to_string();
+    assert!(output.contains("MyEnum :: Other"));
+    let output = extract_variant_core::derive_variant(quote! {
+        #[variant_of(MyEnum, strict = false, into = make, from = unmake)]
+        struct Variant;
+    })
+    .to_string();
+    assert!(output.contains("(make) (value)"));
+    assert!(output.contains("(unmake) (value)"));
+    let output = extract_variant_core::derive_variant(quote! {
+        #[variant_of(MyEnum, Other, marker_only)]
+        struct Variant(u8);
+    })
+    .to_string();
+    assert_eq!(
+        output,
+        quote!(impl ::variant_traits::Variant<MyEnum> for Variant {}).to_string()
+    );
+    let output = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Tree, Node, boxed)]
+        struct NodeData(u8);
+    })
+    .to_string();
+    assert!(output.contains("Self :: Node (:: std :: boxed :: Box :: new (value))"));
+    assert!(output.contains("Ok (* boxed)"));
+    let output = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Value, Number, transparent)]
+        struct Distance { meters: Meters }
+    })
+    .to_string();
+    assert!(output.contains("Self :: Number (:: std :: convert :: Into :: into (value . meters))"));
+    assert!(output.contains("Ok (Self { meters : :: std :: convert :: Into :: into (field) })"));
+    let error = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Value, Number, transparent)]
+        struct Distance(Meters, u8);
+    })
+    .to_string();
+    assert!(error.contains("must have exactly one"));
+    let output = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Shape, Rectangle, helpers)]
+        pub struct Rectangle(f64, f64);
+    })
+    .to_string();
+    assert!(output.contains("pub fn matches (value : & Shape) -> bool"));
+    assert!(output.contains(
+        "Shape :: Rectangle (_0 , _1) => :: std :: option :: Option :: Some ((_0 , _1 ,))"
+    ));
+    let output = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Shape, helpers)]
+        struct Rectangle(f64, f64);
+    })
+    .to_string();
+    assert!(output.contains(":: std :: matches ! (value , Shape :: Rectangle (..))"));
+    let error = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Shape, Rectangle, helpers, marker_only)]
+        struct Rectangle(f64, f64);
+    })
+    .to_string();
+    assert!(error.contains("leave unknown"));
+    let output = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Signal, Interrupt, discriminant = 2, repr = u8)]
+        pub struct Interrupt;
+    })
+    .to_string();
+    assert!(output.contains("pub const DISCRIMINANT : u8 = 2 ;"));
+    assert!(output.contains("Signal :: Interrupt as u8 == Interrupt :: DISCRIMINANT"));
+    let error = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Signal, Interrupt, discriminant = 2)]
+        pub struct Interrupt(u8);
+    })
+    .to_string();
+    assert!(error.contains("only available on unit structs"));
+    let error = extract_variant_core::derive_variant(quote! {
+        #[variant_of(Tree, Node, boxed, marker_only)]
+        struct NodeData(u8);
+    })
+    .to_string();
+    assert!(error.contains("compile_error"));
+    let error = extract_variant_core::derive_variant(quote! {
+        struct Variant(u8);
+    })
+    .to_string();
+    assert!(error.contains("compile_error"));
+}
+
+#[test]
+fn cfg_on_tuple_fields() {
+    let error = expand_error(quote! {
+        enum MyEnum {
+            Variant(#[cfg(unix)] i32, u8),
+        }
+    });
+    assert!(error.contains("only supported on named fields"));
+}
+
+#[test]
+fn unchecked_getters_need_repr_c_structs() {
+    let output = expand(quote! {
+        #[extract_variant(unchecked_getters)]
+        #[repr(C, u8)]
+        enum MyEnum {
+            #[variant_attrs(repr(C))]
+            Pair(u8, u64),
+        }
+    });
+    assert_eq!(attrs(find_struct(&output, "Pair"))[0], "# [repr (C)]");
+    for repr in [quote!(repr(packed)), quote!(repr(C, align(16)))] {
+        let error = expand_error(quote! {
+            #[extract_variant(unchecked_getters)]
+            #[repr(C, u8)]
+            enum MyEnum {
+                #[variant_attrs(#repr)]
+                Pair(u8, u64),
+            }
+        });
+        assert!(error.contains("needs it to be `#[repr(C)]` only"));
+    }
+}
+
+#[test]
+fn display_needs_formats() {
+    let error = expand_error(quote! {
+        #[extract_variant(display)]
+        enum MyEnum {
+            Tuple(u8),
+        }
+    });
+    assert!(error.contains("mark it `#[display_skip]`"));
+    let error = expand_error(quote! {
+        #[extract_variant(display)]
+        enum MyEnum {
+            #[display_fmt("{_0}")]
+            #[display_fmt("{_0}!")]
+            Tuple(u8),
+        }
+    });
+    assert!(error.contains("duplicate #[display_fmt] attribute"));
+}
+
+#[test]
+fn inherit_derives() {
+    let output = expand(quote! {
+        #[extract_variant(inherit_derives(Debug, Clone))]
+        #[derive(Debug, Clone, PartialEq)]
+        #[derive(Default)]
+        enum MyEnum {
+            #[default]
+            Unit,
+            #[variant_derive(PartialEq)]
+            Tuple(f64),
+        }
+    });
+    let unit = find_struct(&output, "Unit");
+    assert_eq!(
+        attrs(unit),
+        [
+            "# [derive (Debug , Clone)]",
+            "# [doc (alias = \"MyEnum::Unit\")]"
+        ]
+    );
+    let tuple = find_struct(&output, "Tuple");
+    assert_eq!(
+        attrs(tuple),
+        [
+            "# [derive (Debug , Clone)]",
+            "# [derive (PartialEq)]",
+            "# [doc (alias = \"MyEnum::Tuple\")]"
+        ]
+    );
+    let all = expand(quote! {
+        #[extract_variant(inherit_derives(*))]
+        #[derive(Debug, Clone)]
+        enum MyEnum { Unit }
+    });
+    assert_eq!(
+        attrs(find_struct(&all, "Unit")),
+        [
+            "# [derive (Debug , Clone)]",
+            "# [doc (alias = \"MyEnum::Unit\")]"
+        ]
+    );
+
+    let error = expand_error(quote! {
+        #[extract_variant(inherit_derives(Hash))]
+        #[derive(Debug)]
+        enum MyEnum { Unit }
+    });
+    assert!(error.contains("can only list traits derived by the enum"));
+}
+
+#[test]
+fn options_separated_by_commas() {
+    let spaced = expand(quote! {
+        #[extract_variant(prefix(Pre) suffix(Post) kind)]
+        enum MyEnum { Variant }
+    });
+    let commas = expand(quote! {
+        #[extract_variant(prefix(Pre), suffix(Post), kind,)]
+        enum MyEnum {
+            #[extract_variant(display_skip, exclude,)]
+            Variant,
+        }
+    });
+    assert!(has_struct(&spaced, "PreVariantPost"));
+    assert!(!has_struct(&commas, "PreVariantPost"));
+    let mixed = expand(quote! {
+        #[extract_variant(prefix(Pre), suffix(Post) kind)]
+        enum MyEnum { Variant }
+    });
+    assert_eq!(
+        quote! { #mixed }.to_string(),
+        quote! { #spaced }.to_string()
+    );
+
+    for (input, message) in [
+        (
+            quote! { #[extract_variant(, kind)] },
+            "expected an option before the comma",
+        ),
+        (
+            quote! { #[extract_variant(kind,, exploded)] },
+            "expected an option after the comma",
+        ),
+        (
+            quote! { #[extract_variant(kind = true)] },
+            "expected the name of an option",
+        ),
+        (
+            quote! { #[extract_variant(knid)] },
+            "invalid parameter name `knid`",
+        ),
+    ] {
+        let error = expand_error(quote! {
+            #input
+            enum MyEnum { Variant }
+        });
+        assert!(error.contains(message), "{}", error);
+    }
+}
+
+#[test]
+fn allow_unused() {
+    let file = expand(quote! {
+        #[extract_variant(allow_unused allow(clippy::all))]
+        enum MyEnum {
+            Variant(u8),
+        }
+    });
+    assert!(attrs(find_struct(&file, "Variant"))
+        .contains(&"# [allow (clippy :: all , dead_code)]".to_owned()));
+    assert!(file.items.iter().all(|item| match item {
+        syn::Item::Impl(item) => item
+            .attrs
+            .iter()
+            .any(|attr| attr.to_token_stream().to_string().contains("dead_code")),
+        _ => true,
+    }));
+    let file = expand(quote! {
+        enum MyEnum {
+            Variant(u8),
+        }
+    });
+    assert!(attrs(find_struct(&file, "Variant"))
+        .iter()
+        .all(|attr| !attr.contains("dead_code")));
+}
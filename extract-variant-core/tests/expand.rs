@@ -0,0 +1,362 @@
+use quote::quote;
+
+#[test]
+fn extract_variant_expansion() {
+    let expanded = extract_variant_core::extract_variant(quote! {
+        enum MyEnum {
+            UnitVariant,
+            TupleVariant(i32),
+        }
+    });
+    let expected = quote! {
+        #[doc(alias = "MyEnum::UnitVariant")]
+        struct UnitVariant;
+        impl UnitVariant {
+            /// The variant in the enum, for const contexts where the conversions aren't
+            /// available.
+            const VALUE: MyEnum = MyEnum::UnitVariant;
+        }
+        impl ::std::convert::From<UnitVariant> for MyEnum {
+            fn from(UnitVariant: UnitVariant) -> Self {
+                Self::UnitVariant
+            }
+        }
+        impl ::std::convert::TryFrom<MyEnum> for UnitVariant {
+            type Error = MyEnum;
+            fn try_from(value: MyEnum) -> ::std::result::Result<Self, Self::Error> {
+                if let MyEnum::UnitVariant = value { Ok(UnitVariant) } else { Err(value) }
+            }
+        }
+        impl ::variant_traits::Variant<MyEnum> for UnitVariant {}
+        #[doc(alias = "MyEnum::TupleVariant")]
+        struct TupleVariant(pub i32);
+        impl ::std::convert::From<TupleVariant> for MyEnum {
+            fn from(TupleVariant(_0): TupleVariant) -> Self {
+                Self::TupleVariant(_0)
+            }
+        }
+        impl ::std::convert::TryFrom<MyEnum> for TupleVariant {
+            type Error = MyEnum;
+            fn try_from(value: MyEnum) -> ::std::result::Result<Self, Self::Error> {
+                if let MyEnum::TupleVariant(_0) = value { Ok(TupleVariant(_0)) } else { Err(value) }
+            }
+        }
+        impl ::variant_traits::Variant<MyEnum> for TupleVariant {}        impl ::variant_traits::Variants for MyEnum {
+            type List = (UnitVariant, TupleVariant,);
+        }
+    };
+    assert_eq!(expanded.to_string(), expected.to_string());
+}
+
+#[test]
+fn derive_variant_expansion() {
+    let expanded = extract_variant_core::derive_variant(quote! {
+        #[variant_of(my_mod::MyEnum, TupleVariant)]
+        struct MyStruct(i32);
+    });
+    let expected = quote! {
+        impl ::std::convert::From<MyStruct> for my_mod::MyEnum {
+            fn from(MyStruct(_0): MyStruct) -> Self {
+                Self::TupleVariant(_0)
+            }
+        }
+        impl ::std::convert::TryFrom<my_mod::MyEnum> for MyStruct {
+            type Error = my_mod::MyEnum;
+            fn try_from(value: my_mod::MyEnum) -> ::std::result::Result<Self, Self::Error> {
+                if let my_mod::MyEnum::TupleVariant(_0) = value { Ok(MyStruct(_0)) } else { Err(value) }
+            }
+        }
+        impl ::variant_traits::Variant<my_mod::MyEnum> for MyStruct {}
+    };
+    assert_eq!(expanded.to_string(), expected.to_string());
+}
+
+#[test]
+fn extract_variants_of_expansion() {
+    let expanded = extract_variant_core::extract_variants_of(quote! {
+        pub my_mod::MyEnum {
+            TupleVariant(i32),
+            #[exclude]
+            StructVariant { field: f64 },
+            ..
+        }
+    });
+    let expected = quote! {
+        #[doc(alias = "MyEnum::TupleVariant")]
+        pub struct TupleVariant(pub i32);
+        impl ::std::convert::From<TupleVariant> for my_mod::MyEnum {
+            fn from(TupleVariant(_0): TupleVariant) -> Self {
+                Self::TupleVariant(_0)
+            }
+        }
+        impl ::std::convert::TryFrom<my_mod::MyEnum> for TupleVariant {
+            type Error = my_mod::MyEnum;
+            fn try_from(value: my_mod::MyEnum) -> ::std::result::Result<Self, Self::Error> {
+                if let my_mod::MyEnum::TupleVariant(_0) = value { Ok(TupleVariant(_0)) } else { Err(value) }
+            }
+        }
+        impl ::variant_traits::Variant<my_mod::MyEnum> for TupleVariant {}
+        const _: () = {
+            #[allow(dead_code, unreachable_patterns)]
+            fn check(value: &my_mod::MyEnum) {
+                match *value {
+                    my_mod::MyEnum::TupleVariant(..) => {}
+                    my_mod::MyEnum::StructVariant { .. } => {}
+                    _ => {}
+                }
+            }
+        };
+    };
+    assert_eq!(expanded.to_string(), expected.to_string());
+}
+
+#[test]
+fn extract_variants_of_leaves_the_enum_alone() {
+    for options in [quote!(kind), quote!(to_getters), quote!(display)] {
+        let expanded = extract_variant_core::extract_variants_of(quote! {
+            #[extract_variant(#options)]
+            pub std::cmp::Ordering { Less = -1, Equal, Greater }
+        })
+        .to_string();
+        assert!(expanded.contains("can only be done where it's declared"));
+    }
+    let expanded = extract_variant_core::extract_variants_of(quote! {
+        my_mod::MyEnum {
+            #[code(404)]
+            NotFound,
+        }
+    })
+    .to_string();
+    assert!(expanded.contains("`#[code(...)]` implements a `code` method"));
+}
+
+#[test]
+fn extract_variants_of_cfg_attr() {
+    let expanded = extract_variant_core::extract_variants_of(quote! {
+        #[cfg_attr(test, prefix(Test))]
+        my_mod::MyEnum {
+            UnitVariant,
+        }
+    })
+    .to_string();
+    assert!(expanded.contains(
+        &quote!(
+            #[cfg(all(test))]
+            struct TestUnitVariant;
+        )
+        .to_string()
+    ));
+    assert!(expanded.contains(
+        &quote!(
+            #[cfg(all(not(test)))]
+            struct UnitVariant;
+        )
+        .to_string()
+    ));
+}
+
+#[test]
+fn extract_variants_shares_attributes() {
+    let expanded = extract_variant_core::extract_variants(quote! {
+        #![extract_variant(kind)]
+        #![prefix(Self)]
+        enum First {
+            UnitVariant,
+        }
+        #[extract_variant(exploded)]
+        enum Second {
+            UnitVariant,
+        }
+    });
+    let expected = quote! {
+        #[derive(::extract_variant::extract_variant)]
+        #[extract_variant(kind)]
+        #[prefix(First)]
+        enum First {
+            UnitVariant,
+        }
+        #[derive(::extract_variant::extract_variant)]
+        #[extract_variant(kind exploded)]
+        #[prefix(Second)]
+        enum Second {
+            UnitVariant,
+        }
+    };
+    assert_eq!(expanded.to_string(), expected.to_string());
+}
+
+#[test]
+fn assert_variant_shape_destructures_both_sides() {
+    let expanded = extract_variant_core::assert_variant_shape(quote! {
+        MyStruct, my_mod::MyEnum::TupleVariant(i32, String)
+    });
+    let expected = quote! {
+        const _: () = {
+            #[allow(dead_code, irrefutable_let_patterns)]
+            fn check(value: my_mod::MyEnum, variant: MyStruct) {
+                let MyStruct(_0, _1) = variant;
+                let _: i32 = _0;
+                let _: String = _1;
+                if let my_mod::MyEnum::TupleVariant(_0, _1) = value {
+                    let _: i32 = _0;
+                    let _: String = _1;
+                }
+            }
+        };
+    };
+    assert_eq!(expanded.to_string(), expected.to_string());
+    let expanded = extract_variant_core::assert_variant_shape(quote! { MyStruct, Variant });
+    assert!(expanded.to_string().contains("compile_error"));
+}
+
+#[test]
+fn empty_enum_expands_to_nothing() {
+    let expanded = extract_variant_core::extract_variant(quote! {
+        #[extract_variant(kind exploded handler to_getters hygienic)]
+        enum Never {}
+    });
+    assert!(expanded.is_empty());
+}
+
+#[test]
+fn errors_become_compile_errors() {
+    let expanded = extract_variant_core::extract_variant(quote! {
+        enum MyEnum<T> {
+            TupleVariant(T),
+        }
+    });
+    assert!(expanded.to_string().contains("compile_error"));
+}
+
+#[test]
+fn hygienic_expansion() {
+    let expanded = extract_variant_core::extract_variant(quote! {
+        #[extract_variant(hygienic)]
+        pub(super) enum MyEnum {
+            UnitVariant,
+        }
+    });
+    let expected = quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod __extract_variant_MyEnum {
+            use super::*;
+            #[doc(alias = "MyEnum::UnitVariant")]
+            pub(in super::super) struct UnitVariant;
+            impl UnitVariant {
+                /// The variant in the enum, for const contexts where the conversions aren't
+                /// available.
+                pub(in super::super) const VALUE: MyEnum = MyEnum::UnitVariant;
+            }
+            impl ::std::convert::From<UnitVariant> for MyEnum {
+                fn from(UnitVariant: UnitVariant) -> Self {
+                    Self::UnitVariant
+                }
+            }
+            impl ::std::convert::TryFrom<MyEnum> for UnitVariant {
+                type Error = MyEnum;
+                fn try_from(value: MyEnum) -> ::std::result::Result<Self, Self::Error> {
+                    if let MyEnum::UnitVariant = value { Ok(UnitVariant) } else { Err(value) }
+                }
+            }
+            impl ::variant_traits::Variant<MyEnum> for UnitVariant {}
+            impl ::variant_traits::Variants for MyEnum {
+                type List = (UnitVariant,);
+            }
+        }
+        pub(super) use __extract_variant_MyEnum::{UnitVariant};
+    };
+    assert_eq!(expanded.to_string(), expected.to_string());
+}
+
+#[test]
+fn expansion_is_deterministic_and_ordered() {
+    let input = quote! {
+        #[extract_variant(kind kind_strings exploded handler to_getters indices fixtures match_macro)]
+        #[derive(Debug, Clone)]
+        pub enum MyEnum {
+            Zebra(u8),
+            #[exclude]
+            Middle,
+            Apple { field: String },
+            Mango,
+        }
+    };
+    let expanded = extract_variant_core::extract_variant(input.clone()).to_string();
+    for _ in 0..8 {
+        assert_eq!(
+            extract_variant_core::extract_variant(input.clone()).to_string(),
+            expanded
+        );
+    }
+
+    // Each kind of item follows the declaration order of the variants, not their names
+    let positions = |needles: &[&str]| -> Vec<usize> {
+        needles
+            .iter()
+            .map(|needle| expanded.find(needle).expect(needle))
+            .collect()
+    };
+    let is_sorted = |positions: Vec<usize>| positions.windows(2).all(|pair| pair[0] < pair[1]);
+    assert!(is_sorted(positions(&[
+        "struct Zebra",
+        "struct Apple",
+        "struct Mango"
+    ])));
+    assert!(is_sorted(positions(&[
+        "fn to_zebra",
+        "fn to_apple",
+        "fn to_mango"
+    ])));
+    assert!(is_sorted(positions(&[
+        "enum MyEnumKind { Zebra , Middle , Apple , Mango }",
+        "fn on_zebra",
+        "fn on_middle",
+        "fn on_apple",
+        "fn on_mango",
+    ])));
+}
+
+#[test]
+fn library_api_over_syn_types() {
+    let input: syn::DeriveInput = syn::parse_quote! {
+        #[extract_variant(prefix(Ev))]
+        enum MyEnum {
+            UnitVariant,
+            #[exclude]
+            Skipped(u8),
+            TupleVariant(i32),
+        }
+    };
+    let names = extract_variant_core::struct_names(&input).unwrap();
+    let names: Vec<_> = names
+        .iter()
+        .map(|(variant, name)| (variant.to_string(), name.as_ref().map(ToString::to_string)))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("UnitVariant".to_string(), Some("EvUnitVariant".to_string())),
+            ("Skipped".to_string(), None),
+            (
+                "TupleVariant".to_string(),
+                Some("EvTupleVariant".to_string())
+            ),
+        ]
+    );
+
+    let expanded = extract_variant_core::expand_enum(&input)
+        .unwrap()
+        .to_string();
+    assert!(expanded.contains("struct EvTupleVariant"));
+    let foreign =
+        extract_variant_core::expand_foreign_enum(&input, syn::parse_quote!(other::MyEnum))
+            .unwrap()
+            .to_string();
+    assert!(foreign.contains("other :: MyEnum"));
+
+    let input: syn::DeriveInput = syn::parse_quote! {
+        struct NotAnEnum;
+    };
+    assert!(extract_variant_core::expand_enum(&input).is_err());
+}
@@ -1,45 +1,9 @@
-#![feature(rustdoc_missing_doc_code_examples)]
-#![deny(rustdoc::missing_doc_code_examples)]
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 
 extern crate proc_macro;
-extern crate proc_macro2;
-extern crate quote;
-extern crate syn;
 
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
-use quote::quote;
-use syn::{
-    parenthesized,
-    parse::{Parse, ParseStream},
-    parse_macro_input, token, Fields, FieldsNamed, FieldsUnnamed, Generics, ItemEnum, ItemStruct,
-    Path, Token, Variant, VisPublic, Visibility,
-};
-
-// mod lib;
-mod extract_variant;
-mod variant_of;
-
-/// A struct that holds the configuration for the [extract_variant] procedural macro.
-#[derive(Debug, Default)]
-struct ExtractVariant {
-    /// An optional prefix to be added to the names of the generated structs.
-    prefix: Option<Ident>,
-    /// An optional suffix to be added to the names of the generated structs.
-    suffix: Option<Ident>,
-    /// A flag indicating whether the [Into], [TryFrom], and [Variant][variant_traits::Variant] traits should be implemented automatically for the generated structs.
-    no_impl: bool,
-}
-
-/// A struct that holds the configuration for the [variant_of] attribute.
-struct VariantOf {
-    /// The path to the enum that the struct corresponds to.
-    enum_path: Path,
-    /// The name of the variant in the enum that the struct corresponds to.
-    variant_ident: Option<Ident>,
-}
 
 /// Extracts each variant in an enum into its own standalone struct, then implements conversion traits
 /// between the original enum and the generated struct.
@@ -71,6 +35,48 @@ struct VariantOf {
 /// traits to be implemented. It can be useful if you want to ensure that a certain type can be
 /// converted into and from an enum in a consistent way.
 ///
+/// In the other direction, [`HasVariant<V>`][variant_traits::HasVariant] is implemented for `E`,
+/// so that generic code can be bounded on the enum instead. It comes from a blanket impl over every
+/// [`Variant`][variant_traits::Variant], so nothing extra is generated for it.
+///
+/// The struct of a unit variant also has a `VALUE` constant holding the variant in the enum, e.g.
+/// `UnitVariant::VALUE`, for constants and statics where the conversions can't be called.
+///
+/// On nightly, the `nightly_const` feature of this crate makes the conversions `impl const`, so
+/// that they can be called there too. The crate using them has to enable
+/// `#![feature(const_trait_impl, const_convert)]`; without the feature, nothing changes.
+///
+/// The enum also implements [`Variants`][variant_traits::Variants], whose `List` is the tuple of
+/// the extracted structs in the order chosen by [`order`](#order), declaration order by default,
+/// e.g. `(UnitVariant, TupleVariant, StructVariant)`.
+///
+/// [`Variant<E>`][variant_traits::Variant] also provides `into_enum()` and `from_enum()`, which
+/// read better than the conversion traits at call sites:
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// use variant_traits::Variant;
+///
+/// #[derive(extract_variant)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+/// }
+/// fn main() {
+///     let my_enum = TupleVariant(42).into_enum();
+///     let tuple_variant = TupleVariant::from_enum(my_enum);
+/// }
+/// ```
+///
+/// Any variant can also be converted into another one through the enum with
+/// [`convert`][variant_traits::convert()], which only succeeds when both types stand for the same
+/// variant.
+///
+/// With these, the methods of [`EnumExt`][variant_traits::EnumExt] are available on the enum, such
+/// as `map_variant()` which maps the value of a variant in place, and those of
+/// [`VariantIterExt`][variant_traits::VariantIterExt] on any iterator: `collect_into_enum()` wraps
+/// each variant into the enum, e.g. from a `Vec<TupleVariant>` to a `Vec<MyEnum>`, and
+/// `filter_variants::<V>()` only keeps the enums holding the variant `V`, unwrapped.
+///
 /// This behavior can be disabled when desired using the `#[no_impl]` attribute.
 /// ```rust, no_run
 /// # use extract_variant::extract_variant;
@@ -131,6 +137,21 @@ struct VariantOf {
 ///
 /// Note that both the prefix and suffix are optional, and the generated structs will
 /// have the same names as the variants in the original enum if no `prefix` or `suffix` is specified.
+/// A common prefix of the variants can also be removed with the [`trim_prefix`](#trim_prefix)
+/// option.
+///
+/// Like any option, they can depend on the configuration through `#[cfg_attr(...)]`, which is
+/// applied before the options are read, so that only the ones of the current configuration count.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[cfg_attr(test, prefix(Test))]
+/// #[cfg_attr(not(test), prefix(Release))]
+/// enum MyEnum {
+///     UnitVariant,
+/// }
+/// # fn main() {}
+/// ```
 ///
 /// # Attributes
 /// Attributes can be added to the generated structs by specifying the #[variant_attrs(...)] attribute
@@ -138,7 +159,9 @@ struct VariantOf {
 /// a convenient shortcut exists. Firstly, `derive` attributes specified on the enum will be inherited
 /// by all extracted variants. **Important note.[^important note]**
 /// This can save you the effort of specifying the same `derive` attributes on each variant individually.
-/// Secondly, the doc comments of a variant are also passed on to the generated struct.
+/// Secondly, the doc comments of a variant are also passed on to the generated struct, along with
+/// `#[doc(alias)]`es so that searching the docs for `MyEnum::TupleVariant` (or `TupleVariant`, when
+/// the struct is renamed) finds the struct.
 ///
 /// ```rust, no_run
 /// # use extract_variant::extract_variant;
@@ -165,6 +188,153 @@ struct VariantOf {
 /// }
 /// ```
 ///
+/// Traits that are too strong for the other variants but always hold for unit variants, such as
+/// `Default` or `Copy`, can be derived by the structs of unit variants only with the
+/// `unit_derives(...)` option.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(unit_derives(Default, Clone, Copy))]
+/// #[derive(Debug)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(String),
+/// }
+/// fn main() {
+///     let unit_variant = UnitVariant::default();
+///     let copy = unit_variant;
+///     println!("{:?}", unit_variant);
+/// }
+/// ```
+///
+/// Likewise, the structs of variants with a single unnamed field are newtypes, which can derive the
+/// traits of `newtype_derives(...)` only, e.g. those of
+/// [`derive_more`](https://docs.rs/derive_more) to convert from their field and deref to it.
+/// ```rust, ignore
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(newtype_derives(derive_more::From, derive_more::Into, derive_more::Deref))]
+/// enum Id {
+///     User(u64),
+///     Group(u64),
+/// }
+/// fn main() {
+///     let user = User::from(42);
+///     assert_eq!(*user, 42);
+/// }
+/// ```
+///
+/// The structs can also inherit only some of the derives of the enum, listed in
+/// `inherit_derives(...)` and written as they are on the enum, while `inherit_derives(*)` keeps
+/// inheriting all of them. The other traits can still be derived by single structs with
+/// `#[variant_derive(...)]`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(inherit_derives(Debug, Clone))]
+/// #[derive(Debug, Clone, PartialEq, Default)]
+/// enum MyEnum {
+///     #[default]
+///     UnitVariant,
+///     #[variant_derive(PartialEq)]
+///     TupleVariant(f64),
+/// }
+/// fn main() {
+///     assert_eq!(TupleVariant(1.0).clone(), TupleVariant(1.0));
+/// }
+/// ```
+///
+/// Paths starting with `Self` in the passed on docs, of the variants and of their fields, are
+/// rewritten to keep meaning what they did on the enum: `[Self::OtherVariant]` links to the struct
+/// of `OtherVariant` if it has one, and to `MyEnum::OtherVariant` otherwise, and `[Self]` to the
+/// enum. Doc comments that only make sense on the enum can be
+/// kept off the structs with the `no_doc_inherit` option, or per variant with `#[no_doc_inherit]`.
+/// A variant marked `#[doc_inherit]` passes its docs on even with the option.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(no_doc_inherit)]
+/// enum MyEnum {
+///     /// The opposite of [Self::TupleVariant]
+///     UnitVariant,
+///     /// A variant holding a number
+///     #[doc_inherit]
+///     TupleVariant(i32),
+/// }
+/// # fn main() {}
+/// ```
+///
+/// For the most common case of deriving extra traits on a single struct, `#[variant_derive(...)]`
+/// is a shorter form of `#[variant_attrs(#[derive(...)])]`, which also skips the traits that are
+/// already inherited from the enum. Inside `#[variant_attrs(...)]`, attributes can also be written
+/// without their `#[...]` wrapper, separated by commas.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum MyEnum {
+///     #[variant_derive(Eq, Hash, Clone)]
+///     TupleVariant(i32),
+///     #[variant_attrs(derive(Default), doc = "A variant of [MyEnum]")]
+///     StructVariant { field: f64, },
+/// }
+/// fn main() {
+///     let set = std::collections::HashSet::from([TupleVariant(42)]);
+///     let struct_variant = StructVariant::default();
+/// }
+/// ```
+///
+/// The variant also stands for a variant of the kind enum of [`kind`](#kind) and of the exploded
+/// enum of [`exploded`](#exploded). An entry of `#[variant_attrs(...)]` goes on one of those
+/// instead of the struct when preceded by `kind:` or `exploded:`, or explicitly on the struct with
+/// `owned:`. This applies to excluded variants too, which still have a kind and an exploded variant.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind exploded)]
+/// #[derive(Debug)]
+/// enum MyEnum {
+///     #[variant_attrs(kind: doc = "The kind of a unit variant", derive(Default))]
+///     UnitVariant,
+///     #[variant_attrs(exploded: #[doc = "Holds a `TupleVariant`"])]
+///     TupleVariant(i32),
+/// }
+/// fn main() {
+///     let unit_variant = UnitVariant::default();
+/// }
+/// ```
+///
+/// Attributes for the conversions of a variant rather than its struct, e.g. to only compile them
+/// with a feature, go in `#[impl_attrs(...)]`, written the same way. They are put on each of the
+/// `From`, `TryFrom` and `Variant` implementations of the struct.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum MyEnum {
+///     #[impl_attrs(#[cfg(feature = "conversions")])]
+///     TupleVariant(i32),
+/// }
+/// # fn main() {}
+/// ```
+///
+/// A named field with a `#[cfg(...)]` attribute keeps it in the struct, and in each place the
+/// conversions bind or build it, so that the struct and its conversions compile with or without
+/// the field. A field of a tuple can't be configured out, as its fields are converted by position.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum Handle {
+///     File {
+///         #[cfg(unix)]
+///         fd: i32,
+///         #[cfg(windows)]
+///         handle: usize,
+///         path: String,
+///     },
+/// }
+/// # fn main() {}
+/// ```
+///
 /// [^important note]: Any other traits placed within the same derive block as the `extract_variant`
 /// will NOT be inheritted by the generated structs. Be sure to place the traits you want derived
 /// by the generated structs in another block. The derive block with `extract_variant` will only apply
@@ -173,218 +343,1614 @@ struct VariantOf {
 /// a garuanteed behavior or not. If the describe behavior no longer applies in the future,
 /// you can assume that it was not garuanteed.
 ///
-/// # Limitations
-/// Currently, `extract_variant` does not support extracting variants from enums with
-/// generic parameters or lifetime parameters. It can only extract variants from enums
-/// that are monomorphic.
+/// # Boxing fields
+/// A field of a variant marked with `#[boxed]` is stored in a [Box] in the generated struct, while
+/// one marked with `#[unboxed]` must be a `Box<T>` and is stored as a plain `T`. The conversions box
+/// and unbox the value accordingly.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum Expr {
+///     Literal(#[boxed] [u8; 1024]),
+///     Negate(#[unboxed] Box<Expr>),
+/// }
+/// fn main() {
+///     let literal: Box<[u8; 1024]> = Literal(Box::new([0; 1024])).0;
+///     let expr = Expr::from(Negate(Expr::Literal([0; 1024])));
+/// }
+/// ```
 ///
-/// # TODO
-/// Document `#[exclude]` attribute. Just put it on any variant that shouldn't be extracted.
+/// # Mapping field types
+/// The type of a field can be changed in the generated struct with `#[map_type(...)]` on the
+/// variant, listing `field: VariantType => StructType` pairs separated by commas, where `field` is
+/// the name of the field, or its index for tuple variants. The conversions then convert between the
+/// two types with [Into], so both `From<VariantType> for StructType` and
+/// `From<StructType> for VariantType` must be implemented.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum MyEnum {
+///     #[map_type(name: String => Box<str>)]
+///     StructVariant { name: String, },
+///     #[map_type(0: Vec<u8> => Box<[u8]>)]
+///     TupleVariant(Vec<u8>),
+/// }
+/// fn main() {
+///     let struct_variant = StructVariant { name: Box::from("hello") };
+///     let my_enum = MyEnum::from(struct_variant);
+/// }
+/// ```
 ///
-#[proc_macro_derive(
-    extract_variant,
-    attributes(prefix, suffix, no_impl, variant_attrs, exclude)
-)]
-pub fn extract_variant(input: TokenStream) -> TokenStream {
-    match extract_variant::doit(parse_macro_input!(input)) {
-        Ok(token_stream) => token_stream.into(),
-        Err(err) => err.into_compile_error().into(),
-    }
-}
-
-/// TODO
+/// # Tuple structs
+/// A struct variant marked with `#[tuple]` is extracted into a tuple struct instead, with its
+/// fields in declaration order. Other attributes still refer to the fields by their names.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum MyEnum {
+///     #[tuple]
+///     StructVariant { x: f64, y: f64, },
+/// }
+/// fn main() {
+///     let my_enum = MyEnum::from(StructVariant(1.0, 2.0));
+/// }
+/// ```
 ///
-/// In some cases, you may want to manually create a struct that corresponds to a variant of an enum,
-/// without using [`extract_variant`][extract_variant()] to extract the variant automatically.
-/// This can be useful if you want to create a struct that corresponds to a variant of an enum
-/// that cannot be extracted by [`extract_variant`][extract_variant()] (for example,
-/// because it is defined in another crate or has generic parameters).
+/// # Collections
+/// A variant holding a single collection, marked with `#[into_iterator]`, gets a struct that can be
+/// used in its place: it implements [IntoIterator], by value and by reference, [FromIterator] and
+/// [Deref][std::ops::Deref] to the collection, as far as the collection implements them.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum Value {
+///     Number(f64),
+///     #[into_iterator]
+///     Array(Vec<Value>),
+/// }
+/// fn main() {
+///     let array: Array = vec![Value::Number(1.0)].into_iter().collect();
+///     assert_eq!(array.len(), 1);
+///     for value in &array {}
+/// }
+/// ```
 ///
-/// # Example
+/// # Codes
+/// Variants can be given a `u16` code with `#[code(...)]`, e.g. the status codes of an error enum.
+/// Each extracted struct then gets a `CODE` constant, and the enum a `code()` method returning the
+/// code of any of its variants. Once a variant has a code, every variant needs one, excluded ones
+/// included.
 /// ```rust, no_run
-/// use extract_variant::Variant;
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum MyError {
+///     #[code(404)]
+///     NotFound { path: String },
+///     #[code(500)]
+///     Internal,
+/// }
+/// fn main() {
+///     assert_eq!(NotFound::CODE, 404);
+///     assert_eq!(MyError::Internal.code(), 500);
+/// }
+/// ```
 ///
-/// mod my_mod {
-///     pub enum MyEnum {
-///         UnitVariant,
-///         TupleVariant(i32),
-///         StructVariant { field: f64, },
-///     }   
+/// # Tags
+/// Similarly, variants can be given a `u8` tag with `#[tag(...)]`, e.g. the type byte of a message
+/// in a binary protocol. Each extracted struct then gets a `TAG` constant. With the
+/// [`kind`](#kind) option, the kind enum also gets a `tag()` method and a [`TryFrom<u8>`]
+/// implementation, whose error is the unknown tag.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind)]
+/// enum Message {
+///     #[tag(0x01)]
+///     Ping,
+///     #[tag(0x02)]
+///     Data(Vec<u8>),
+/// }
+/// fn main() {
+///     assert_eq!(Data::TAG, 0x02);
+///     assert_eq!(MessageKind::try_from(0x01), Ok(MessageKind::Ping));
+///     assert_eq!(MessageKind::Data.tag(), 0x02);
 /// }
+/// ```
 ///
-/// #[derive(Variant)]
-/// #[variant_of(my_mod::MyEnum)]
-/// struct UnitVariant;
+/// # Fallible conversions
+/// An enum guarding invariants over the fields of a variant shouldn't accept any struct through
+/// [From]. With `#[fallible_from(validator = ..., error = ...)]`, the enum implements [TryFrom] the
+/// struct instead, which first calls the validator, a function from `&Struct` to
+/// `Result<(), Error>`. Unwrapping the struct from the enum is unchanged, but without [From] the
+/// struct isn't a [`Variant`][variant_traits::Variant] of the enum, and the options converting
+/// structs into the enum without the validator can't be used.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum Range {
+///     #[fallible_from(validator = check_bounds, error = String)]
+///     Bounded { low: u32, high: u32 },
+///     Unbounded,
+/// }
+/// fn check_bounds(bounded: &Bounded) -> Result<(), String> {
+///     if bounded.low <= bounded.high {
+///         Ok(())
+///     } else {
+///         Err(format!("{} is above {}", bounded.low, bounded.high))
+///     }
+/// }
+/// fn main() {
+///     assert!(Range::try_from(Bounded { low: 1, high: 2 }).is_ok());
+///     assert!(Range::try_from(Bounded { low: 3, high: 2 }).is_err());
+/// }
+/// ```
 ///
-/// #[derive(Variant)]
-/// #[variant_of(my_mod::MyEnum)]
-/// struct TupleVariant(i32);
+/// # Namespaced attributes
+/// The helper attributes of variants and fields, like `#[exclude]` or `#[boxed]`, have generic names
+/// that other derives may also use. They can all be written inside an `#[extract_variant(...)]`
+/// attribute on the variant or field instead, without their `#[...]`. A helper written this way
+/// takes precedence over the same helper written on its own, which is then left to the other derive.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// enum MyEnum {
+///     #[extract_variant(exclude)]
+///     Excluded,
+///     #[extract_variant(tuple variant_derive(Clone))]
+///     Flattened { #[extract_variant(boxed)] first: u8 },
+/// }
+/// # fn main() {}
+/// ```
 ///
-/// #[derive(Variant)]
-/// #[variant_of(my_mod::MyEnum)]
-/// struct StructVariant {
-///     field: f64,
+/// # Expansion order
+/// The expansion only depends on the tokens of the enum, so the same enum always expands to the
+/// same code, byte for byte, which keeps `cargo expand` diffs and snapshot tests stable. The items
+/// come in a fixed order: the struct of each variant with its conversions and accessors, then the
+/// [`Variants`][variant_traits::Variants] list, the sealed trait, chained conversions, sibling
+/// conversions, the exploded enum, the kind enum, the registry, the handler trait, the `Display`
+/// implementation, dynamic conversions, indices, codes, tags, fixtures and tests, and last the
+/// `match_{enum}!` and `assert_{enum}_variants!` macros. Wherever variants are listed, they follow
+/// the [`order`](#order) option, declaration order by default, except in what mirrors the enum
+/// itself, such as the kind enum. Changing the order of the items, or the default order of the
+/// variants, is considered a breaking change; choosing another order with `order` is not.
+///
+/// # Options
+/// Every enum-level option can also be given in a single `#[extract_variant(...)]` attribute, e.g.
+/// `#[extract_variant(prefix(MyEnum) no_impl)]` is the same as `#[prefix(MyEnum)]` and `#[no_impl]`.
+/// Specifying the same option in both forms is an error. The options are separated by whitespace
+/// or by commas, which may also trail the list, e.g. `#[extract_variant(prefix(MyEnum), no_impl,)]`,
+/// and so are the helpers in an `#[extract_variant(...)]` attribute on a variant or a field. The
+/// options below are only available in this form.
+///
+/// ## `hygienic`
+/// Places the generated structs and implementations inside a hidden module named after the enum,
+/// and re-exports the structs with the enum's visibility. Anything else the macro generates can then
+/// never collide with your own items in the same module.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(hygienic)]
+/// pub enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+/// }
+/// fn main() {
+///     let unit_variant = UnitVariant;
+///     let my_enum: MyEnum = TupleVariant(42).into();
 /// }
 /// ```
-#[proc_macro_derive(Variant, attributes(variant_of))]
-pub fn derive_variant(input: TokenStream) -> TokenStream {
-    match variant_of::doit(parse_macro_input!(input)) {
-        Ok(token_stream) => token_stream.into(),
-        Err(err) => err.into_compile_error().into(),
-    }
-}
-
-/// Generates a struct definition from a variant of an enum.
-///
-/// # Parameters
-///
-/// - `name`: A string slice representing the name of the struct to be generated.
-/// - `item_enum`: A reference to an `ItemEnum` struct representing the original enum.
-/// - `variant`: A reference to a `Variant` struct representing the variant to be extracted.
-///
-/// # Returns
-///
-/// An `ItemStruct` struct representing the generated struct definition.
-fn generate_variant(
-    item_enum: &ItemEnum,
-    variant: &Variant,
-    struct_name: Option<Ident>,
-) -> ItemStruct {
-    let mut fields = variant.fields.clone();
-    for field in &mut fields {
-        field.vis = Visibility::Public(VisPublic {
-            pub_token: token::Pub::default(),
-        });
-    }
-    ItemStruct {
-        attrs: Vec::new(),
-        vis: item_enum.vis.clone(),
-        struct_token: token::Struct(variant.ident.span()),
-        ident: struct_name.unwrap_or_else(|| variant.ident.clone()),
-        generics: Generics::default(),
-        fields,
-        semi_token: None,
-    }
-}
-
-/// Generates a block of code that implements the `Into`, `TryFrom`, and `Variant` traits for a struct that corresponds to a variant of an enum.
 ///
-/// # Parameters
+/// ## `to_getters`
+/// Generates a `to_*` method on the enum for each extracted variant, which clones the fields of
+/// the variant into its struct. The method is named after the variant in `snake_case`, and requires
+/// every field of the variant to be [Clone].
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(to_getters)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(String),
+/// }
+/// fn main() {
+///     let my_enum = MyEnum::TupleVariant(String::from("hello"));
+///     let tuple_variant: Option<TupleVariant> = my_enum.to_tuple_variant();
+///     assert!(my_enum.to_unit_variant().is_none());
+/// }
+/// ```
 ///
-/// - `item_struct`: A reference to an `ItemStruct` struct representing the struct for which the traits should be implemented.
-/// - `enum_path`: A reference to a `Path` struct representing the path to the enum that the struct corresponds to.
-/// - `variant_name`: An optional `Ident` struct representing the name of the variant in the enum that the struct corresponds to. If this parameter is `None`, the function will use the name of the struct as the name of the variant.
+/// ### Naming
+/// The methods can be named `get_*` or after the variant alone instead, with
+/// `accessors(style = "get")` or `accessors(style = "plain")` (`"to"` being the default). A variant
+/// whose name doesn't convert well to `snake_case` can be given another one with
+/// `#[accessor_name(...)]`, which the style still applies to.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(to_getters accessors(style = "get"))]
+/// enum MyEnum {
+///     #[accessor_name(ipv4)]
+///     IPv4([u8; 4]),
+///     Other,
+/// }
+/// fn main() {
+///     let ipv4: Option<IPv4> = MyEnum::Other.get_ipv4();
+/// }
+/// ```
 ///
-/// # Returns
+/// ## `as_getters`
+/// Generates an `as_*` and an `as_*_mut` method on the enum for each variant with fields, which
+/// borrow the fields of the variant without cloning them or going through its struct: a tuple of
+/// references, or a single reference for a variant with one field. Variants without a struct have
+/// them too, but not those excluded with `#[exclude(all)]`. The methods are named after the variant
+/// as with [`to_getters`](#to_getters), or after its `#[accessor_name(...)]`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(as_getters)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32, String),
+///     NamedVariant { id: u64 },
+/// }
+/// fn main() {
+///     let mut my_enum = MyEnum::TupleVariant(1, String::from("hello"));
+///     let fields: Option<(&i32, &String)> = my_enum.as_tuple_variant();
+///     if let Some((_, name)) = my_enum.as_tuple_variant_mut() {
+///         name.push('!');
+///     }
+///     assert!(my_enum.as_named_variant().is_none());
+/// }
+/// ```
 ///
-/// A `TokenStream` representing the generated block of code.
-fn impl_variant(
-    item_struct: &ItemStruct,
-    enum_path: &Path,
-    variant_ident: Option<&Ident>,
-) -> proc_macro2::TokenStream {
-    let struct_path = Path::from(item_struct.ident.clone());
-    let variant_ident = variant_ident.unwrap_or(&item_struct.ident);
-
-    // Create the `From` and `TryFrom` trait implementations
-    let froms = impl_froms(
-        &struct_path,
-        enum_path,
-        variant_ident,
-        fields_stream(&item_struct.fields),
-    );
-
-    // Return a `TokenStream` containing the trait implementations
-    quote! {
-        #froms
-        impl ::variant_traits::Variant<#enum_path> for #struct_path {}
-    }
-}
-
-fn fields_stream(fields: &Fields) -> TokenStream2 {
-    match fields {
-        // If the fields are named, bind the names to variables and use them to create the trait implementations
-        Fields::Named(FieldsNamed { named, .. }) => {
-            let names = named.into_iter().map(|f| f.ident.as_ref().unwrap());
-            quote! { { #(#names),* } }
-        }
-        // If the fields are unnamed, bind the fields to variables with names like "_0", "_1", etc. and use them to create the trait implementations
-        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-            let id = (0..unnamed.len()).map(|i| Ident::new(&format!("_{}", i), Span::call_site()));
-            quote! { (#(#id),*) }
-        }
-        // If the fields are a unit type, bind no variables and use them to create the trait implementations
-        Fields::Unit => quote! {},
-    }
-}
-fn impl_froms(
-    struct_path: &Path,
-    enum_path: &Path,
-    variant_ident: &Ident,
-    fields_stream: proc_macro2::TokenStream,
-) -> proc_macro2::TokenStream {
-    quote! {
-        impl ::std::convert::From<#struct_path> for #enum_path {
-            fn from(#struct_path #fields_stream: #struct_path) -> Self {
-                Self::#variant_ident #fields_stream
-            }
-        }
-        impl ::std::convert::TryFrom<#enum_path> for #struct_path {
-            type Error = #enum_path;
-            fn try_from(value: #enum_path) -> ::std::result::Result<Self, Self::Error> {
-                if let #enum_path::#variant_ident #fields_stream = value { Ok(#struct_path #fields_stream) } else { Err(value) }
-            }
-        }
-    }
-}
-
-// ================================================================================================
-// ------------------------------------------------------------------------------------------------
-// ================================================================================================
-
-impl Parse for ExtractVariant {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut extract_variant = Self::default();
-
-        if input.peek(token::Paren) {
-            let content;
-            parenthesized!(content in input);
-
-            while !content.is_empty() {
-                let ident: Ident = content.parse()?;
-                match ident.to_string().as_ref() {
-                    "prefix" => {
-                        let inner_content;
-                        parenthesized!(inner_content in content);
-                        extract_variant.prefix = Some(inner_content.parse()?)
-                    }
-                    "suffix" => {
-                        let inner_content;
-                        parenthesized!(inner_content in content);
-                        extract_variant.suffix = Some(inner_content.parse()?)
-                    }
-                    "no_impl" => extract_variant.no_impl = true,
-                    _ => return Err(syn::Error::new(ident.span(), "invalid parameter name")),
-                }
-            }
-        }
-
-        Ok(extract_variant)
-    }
-}
-
-impl Parse for VariantOf {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let enum_path = input.parse()?;
-        let variant_ident = if input.peek(Token![,]) {
-            input.parse::<Token![,]>()?;
-            Some(input.parse()?)
-        } else {
-            None
-        };
-        Ok(Self {
-            enum_path,
-            variant_ident,
-        })
-    }
+/// ## `unchecked_getters`
+/// The structs of a `#[repr(C)]` enum, or `#[repr(C, u8)]` and so on, are `#[repr(C)]` too, so that
+/// a struct with the fields of its variant has the layout of the payload of the variant in the enum.
+/// `unchecked_getters` builds on it with an `unsafe` `as_*_unchecked` method on the enum for each
+/// extracted variant, which borrows the payload in place as the struct without checking the variant,
+/// e.g. to read messages from shared memory without copying them once their tag is known. The
+/// fields of the structs must be those of the variants, without `#[boxed]` or `#[map_type]`, and
+/// the structs can't be given another `repr`, such as `packed` or `align(..)`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(unchecked_getters)]
+/// #[repr(C, u8)]
+/// enum Message {
+///     Ping,
+///     Data { len: u16, bytes: [u8; 64] },
+/// }
+/// fn main() {
+///     let message = Message::Data { len: 0, bytes: [0; 64] };
+///     if let Message::Data { .. } = message {
+///         // SAFETY: the message was just checked to be a `Data`
+///         let data: &Data = unsafe { message.as_data_unchecked() };
+///     }
+/// }
+/// ```
+///
+/// ## `local`
+/// Declares that the enum is inside a function body. Everything generated there stays as visible as
+/// the enum, but a module declared in a function body can't see the items of the function, so the
+/// options generating one, [`hygienic`](#hygienic), [`generate_tests`](#generate_tests) and
+/// [`report_sizes`](#report_sizes), fail
+/// with an explanation instead of unresolved names.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// fn main() {
+///     #[derive(extract_variant)]
+///     #[extract_variant(local kind)]
+///     enum Step {
+///         Forward(u32),
+///         Stop,
+///     }
+///     let step: Step = Forward(3).into();
+///     assert_eq!(step.kind(), StepKind::Forward);
+/// }
+/// ```
+///
+/// ## `pub_in`
+/// Gives the generated items a `pub(in path)` visibility instead of the one of the enum, e.g.
+/// `#[extract_variant(pub_in(crate::model))]`. Like in any restricted visibility, the path must
+/// start with `crate`, `self` or `super`, and name a module the enum is in, which the compiler
+/// checks.
+/// ```rust, no_run
+/// mod model {
+///     mod event {
+///         # use extract_variant::extract_variant;
+///         #[derive(extract_variant)]
+///         #[extract_variant(pub_in(crate::model))]
+///         pub enum Event {
+///             Joined { name: String },
+///         }
+///     }
+///     fn join(name: String) -> event::Event {
+///         event::Joined { name }.into()
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `readonly`
+/// Makes the fields of the generated structs private, as the representation of the enum may not be
+/// meant to be public. Each field gets a getter named after it, or `_0`, `_1`... in tuple structs,
+/// and a `_mut` getter, and `into_parts()` returns all the fields as a tuple. Outside of the enum's
+/// module, or anywhere with [`hygienic`](#hygienic), the structs can then only be built by
+/// converting a variant. Fields with a `#[vis(...)]` attribute keep the visibility it gives them.
+/// ```rust, no_run
+/// mod shapes {
+///     # use extract_variant::extract_variant;
+///     #[derive(extract_variant)]
+///     #[extract_variant(readonly)]
+///     pub enum Shape {
+///         Circle { radius: f64 },
+///         Rectangle(f64, f64),
+///     }
+/// }
+/// use shapes::{Circle, Rectangle, Shape};
+/// fn main() {
+///     let mut circle = Circle::try_from(Shape::Circle { radius: 1.0 }).ok().unwrap();
+///     *circle.radius_mut() *= 2.0;
+///     assert_eq!(*circle.radius(), 2.0);
+///     let rectangle = Rectangle::try_from(Shape::Rectangle(1.0, 2.0)).ok().unwrap();
+///     assert_eq!(rectangle.into_parts(), (1.0, 2.0));
+/// }
+/// ```
+///
+/// ## `from_arc`
+/// Implements `TryFrom<Arc<Enum>>` on each extracted struct, for enums shared behind an
+/// [`Arc`][std::sync::Arc], e.g. the events of a message bus. The fields of the variant are cloned
+/// out of the shared enum, so they must all be [Clone]. On another variant, the `Arc` is given back.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// # use std::sync::Arc;
+/// #[derive(extract_variant)]
+/// #[extract_variant(from_arc)]
+/// enum Event {
+///     Joined { name: String },
+///     Left,
+/// }
+/// fn main() {
+///     let event = Arc::new(Event::Joined { name: "ferris".into() });
+///     if let Ok(Joined { name }) = Joined::try_from(event.clone()) {
+///         assert_eq!(name, "ferris");
+///     }
+///     assert!(Left::try_from(event).is_err());
+/// }
+/// ```
+///
+/// ## `exploded`
+/// Generates a `{Enum}Exploded` enum with the same variants as the original enum, where each
+/// variant holds its extracted struct, along with [From] conversions in both directions. Matching
+/// on it is exhaustive, unlike chaining [TryFrom] calls. Excluded variants keep their fields, so no
+/// information is lost in either direction. The exploded enum inherits the `derive`s of the enum,
+/// except for `Default`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(exploded)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+///     StructVariant { field: f64, },
+/// }
+/// fn main() {
+///     match MyEnumExploded::from(MyEnum::TupleVariant(42)) {
+///         MyEnumExploded::UnitVariant(unit_variant) => {}
+///         MyEnumExploded::TupleVariant(tuple_variant) => {}
+///         MyEnumExploded::StructVariant(struct_variant) => {}
+///     }
+/// }
+/// ```
+///
+/// ## `generate_tests`
+/// Generates a `#[cfg(test)]` module with a round-trip test for each extracted variant, which
+/// converts a value of the variant from the struct to the enum and back, and from the enum to the
+/// struct and back. The values are built from the [Default] of each field, so variants with a field
+/// that doesn't implement [Default] are skipped, and they are only compared when they implement
+/// [PartialEq]. Cannot be combined with `no_impl`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(generate_tests)]
+/// #[derive(PartialEq)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+///     StructVariant { field: f64, },
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `report_sizes`
+/// Generates a `#[cfg(test)]` module with a `report_sizes` test printing the size of the enum and of
+/// each extracted struct, largest first, to find the variants that make the enum large (as
+/// reported by `clippy::large_enum_variant`) and decide which fields to [`#[boxed]`](#boxing-fields).
+/// Run it with `cargo test report_sizes -- --nocapture` to see the sizes.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(report_sizes)]
+/// enum Packet {
+///     Ping,
+///     Data([u8; 512]),
+///     Ack { id: u64 },
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `skip_unit`
+/// Doesn't generate structs for unit variants, as if they were all marked `#[exclude]`. Useful on
+/// enums with many field-less variants, whose empty structs would only clutter the namespace.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(skip_unit)]
+/// enum Command {
+///     Quit,
+///     Pause,
+///     Move(i32, i32),
+///     Say { text: String },
+/// }
+/// fn main() {
+///     let command: Command = Move(1, 2).into();
+/// }
+/// ```
+///
+/// ## `kind`
+/// Generates a `{Enum}Kind` enum with the same variants as the original enum but without their
+/// fields, excluded variants included, and a `kind()` method on the enum returning the kind of any
+/// of its variants. Each extracted struct also gets a `KIND` constant, to go from the type of a
+/// variant to its kind without a value. The kind enum is `Copy` and can be compared. It keeps the explicit
+/// discriminants of the variants and the `#[repr(...)]` of the enum, so a kind casts to the same
+/// value as its variant.
+///
+/// `#[exclude]`, or `#[exclude(struct_only)]`, only skips the struct of a variant: it keeps its kind,
+/// its handler method and its place everywhere else. `#[exclude(all)]` pretends the variant doesn't
+/// exist instead: it has no kind, no handler method, no tag and isn't counted by `indices`, and
+/// `kind()` returns an [Option] that is `None` for it. `#[exclude(kind_only)]` is the same, except
+/// that the variant keeps its kind, so that `kind()` still covers every variant, and so does the
+/// [`WrongVariant`][variant_traits::WrongVariant] error of [`wrong_variant`](#wrong_variant).
+/// `#[exclude(keep_impls)]` only leaves out the
+/// declaration of the struct, which is declared by hand next to the enum instead, e.g. to derive
+/// other traits: everything else is generated as usual, so the struct must have the same fields as
+/// the generated one would.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+///     StructVariant { field: f64, },
+/// }
+/// fn main() {
+///     assert_eq!(MyEnum::TupleVariant(42).kind(), MyEnumKind::TupleVariant);
+///     assert_eq!(StructVariant::KIND, MyEnumKind::StructVariant);
+/// }
+/// ```
+///
+/// ## `kind_strings`
+/// Implements [`Display`][std::fmt::Display] and [`FromStr`][std::str::FromStr] on the kind enum of
+/// [`kind`](#kind), so that kinds can be read from configuration files or command-line arguments.
+/// A kind is written as the name of its variant, unless the variant has a `#[kind_name(...)]`
+/// attribute. Parsing an unknown name fails with a
+/// [`ParseKindError`][variant_traits::ParseKindError].
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind kind_strings)]
+/// enum Format {
+///     Json,
+///     #[kind_name("yaml")]
+///     Yaml(String),
+/// }
+/// fn main() {
+///     assert_eq!(FormatKind::Json.to_string(), "Json");
+///     assert_eq!("yaml".parse(), Ok(FormatKind::Yaml));
+/// }
+/// ```
+///
+/// ## `kind_index`
+/// Numbers the kind enum of [`kind`](#kind) in declaration order, from `0`, as a `#[repr(usize)]`
+/// enum, instead of copying the discriminants and representation of the original enum. The kinds
+/// get an `index()` method and a `from_index()` constructor going back, so that a kind can key a
+/// slice or array of per-variant data.
+///
+/// ## `kind_ord`
+/// Derives [PartialOrd] and [Ord] on the kind enum of [`kind`](#kind). Kinds are ordered by their
+/// discriminants, which is the declaration order with `kind_index` or without explicit
+/// discriminants.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind kind_index kind_ord)]
+/// enum Event {
+///     Start,
+///     Tick(u64),
+///     Stop,
+/// }
+/// fn main() {
+///     assert_eq!(EventKind::Tick.index(), 1);
+///     assert_eq!(EventKind::from_index(2), Some(EventKind::Stop));
+///     assert!(EventKind::Start < EventKind::Stop);
+/// }
+/// ```
+///
+/// ## `kind_repr`
+/// Gives the kind enum of [`kind`](#kind) an integer representation, `u8` by default or the one
+/// given as in `kind_repr(u16)`, instead of the representation of the enum, with the same
+/// discriminants. The kinds can then be stored as numbers: they get an `as_u8()` method (named
+/// after the representation), convert into it, and convert back with [TryFrom], whose
+/// [`InvalidKindError`][variant_traits::InvalidKindError] gives back the unknown number.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind kind_repr)]
+/// #[repr(u8)]
+/// enum Shape {
+///     Circle(f64),
+///     Square(f64) = 4,
+/// }
+/// fn main() {
+///     assert_eq!(ShapeKind::Square.as_u8(), 4);
+///     assert_eq!(ShapeKind::try_from(0), Ok(ShapeKind::Circle));
+///     assert!(ShapeKind::try_from(1).is_err());
+/// }
+/// ```
+///
+/// ## `kind_key`
+/// Derives [Hash] on the kind enum of [`kind`](#kind), which is already [Copy] and [Eq] whatever
+/// the derives of the enum, and adds a `kind_key()` method to the enum returning its kind. Values
+/// can then be grouped by variant in a [`HashMap`][std::collections::HashMap], even when their
+/// fields can't be hashed.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// use std::collections::HashMap;
+///
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind kind_key)]
+/// enum Sample {
+///     Temperature(f64),
+///     Pressure(f64),
+/// }
+/// fn main() {
+///     let mut counts = HashMap::new();
+///     for sample in [Sample::Temperature(1.0), Sample::Pressure(2.0), Sample::Temperature(3.0)] {
+///         *counts.entry(sample.kind_key()).or_insert(0) += 1;
+///     }
+///     assert_eq!(counts[&SampleKind::Temperature], 2);
+/// }
+/// ```
+///
+/// ## `compare_by_kind`
+/// Compares values of the enum by variant alone, whatever their fields: `same_kind()` tells
+/// whether two values are the same variant, and the enum implements [PartialEq] against its kind
+/// enum, so that a value can be compared with a kind directly. Requires [`kind`](#kind).
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind compare_by_kind)]
+/// enum Sample {
+///     Temperature(f64),
+///     Pressure(f64),
+/// }
+/// fn main() {
+///     assert!(Sample::Temperature(1.0).same_kind(&Sample::Temperature(2.0)));
+///     assert!(Sample::Pressure(1.0) == SampleKind::Pressure);
+/// }
+/// ```
+///
+/// ## `registry`
+/// Generates a `{ENUM}_VARIANTS` static (e.g. `MY_ENUM_VARIANTS` for `MyEnum`) listing a
+/// [`VariantInfo`][variant_traits::VariantInfo] for each variant, in the order of
+/// [`order`](#order), for code that discovers the variants at runtime, such as a scripting bridge.
+/// Each one has the name, kind and field names of its variant, and a constructor building the enum
+/// from the exploded enum, so both [`kind`](#kind) and [`exploded`](#exploded) are required.
+/// Variants excluded with `#[exclude(all)]` have no kind and aren't listed, while those excluded
+/// with `#[exclude(kind_only)]` keep their kind and are listed like the other excluded variants.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(kind exploded registry)]
+/// enum Command {
+///     Quit,
+///     Move { x: i32, y: i32 },
+/// }
+/// fn main() {
+///     let info = &COMMAND_VARIANTS[1];
+///     assert_eq!((info.name(), info.field_names()), ("Move", &["x", "y"][..]));
+///     let command = info.construct(CommandExploded::Move(Move { x: 1, y: 2 }));
+///     assert!(matches!(command, Some(Command::Move { x: 1, y: 2 })));
+/// }
+/// ```
+///
+/// ## `handler`
+/// Generates a `{Enum}Handler` trait with an `on_*` method for each variant, named after the
+/// variant in `snake_case`, and a `dispatch()` method on the enum that calls the method of its
+/// variant. Every method does nothing by default, so a handler only implements the variants it
+/// cares about. The methods take the extracted struct, or the enum itself for excluded variants.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(handler)]
+/// enum Event {
+///     KeyPress(char),
+///     Resize { width: u32, height: u32 },
+///     Quit,
+/// }
+/// struct Typist(String);
+/// impl EventHandler for Typist {
+///     fn on_key_press(&mut self, KeyPress(c): KeyPress) {
+///         self.0.push(c);
+///     }
+/// }
+/// fn main() {
+///     let mut typist = Typist(String::new());
+///     Event::KeyPress('a').dispatch(&mut typist);
+///     Event::Quit.dispatch(&mut typist);
+/// }
+/// ```
+///
+/// ## `qualified_debug`
+/// Implements [Debug] on the structs as if they were their variant of the enum, e.g.
+/// `MyEnum::TupleVariant(42)` rather than `TupleVariant(42)`, so that a struct logged on its own
+/// still tells which enum it comes from. The structs then don't inherit the `Debug` derive of the
+/// enum, but their fields must still implement it.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(qualified_debug)]
+/// #[derive(Debug)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+///     StructVariant { field: f64, },
+/// }
+/// fn main() {
+///     assert_eq!(format!("{:?}", TupleVariant(42)), "MyEnum::TupleVariant(42)");
+///     assert_eq!(format!("{:?}", UnitVariant), "MyEnum::UnitVariant");
+/// }
+/// ```
+///
+/// ## `display`
+/// Implements [`Display`][std::fmt::Display] on the enum and on its structs, e.g. for an error enum
+/// whose errors are also used on their own. Each variant gives its format in a
+/// `#[display_fmt("...")]` attribute, referring to its fields by name, or as `_0`, `_1` and so on in
+/// tuple variants, and the enum is formatted as the struct of the variant it holds would be,
+/// without building the struct. Variants marked `#[display_skip]`, excluded ones and unit variants
+/// without a format are written as their name.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(display)]
+/// enum Error {
+///     #[display_fmt("{path} not found")]
+///     NotFound { path: String },
+///     #[display_fmt("error {_0}")]
+///     Code(u16),
+///     #[display_skip]
+///     Timeout,
+/// }
+/// fn main() {
+///     let error = Error::NotFound { path: "a.txt".into() };
+///     assert_eq!(error.to_string(), "a.txt not found");
+///     assert_eq!(Code(404).to_string(), "error 404");
+///     assert_eq!(Error::Timeout.to_string(), "Timeout");
+/// }
+/// ```
+///
+/// ## `dynamic`
+/// Converts the structs and the enum from and into a dynamic value type through serde, e.g.
+/// `dynamic(serde_json::Value)`, for glue code that passes values around untyped. The type's module
+/// must provide `to_value`, `from_value` and an `Error` type, as `serde_json` does: each struct and
+/// the enum get `TryFrom<Value>`, and the value type gets `From` each of them, which panics if the
+/// value has no dynamic representation, such as a map with non-string keys.
+///
+/// The structs and the enum must implement `Serialize` and `Deserialize`, which is easiest done by
+/// deriving them on the enum, as the structs inherit its derives.
+/// ```rust, ignore
+/// # use extract_variant::extract_variant;
+/// use serde::{Deserialize, Serialize};
+/// use serde_json::{json, Value};
+///
+/// #[derive(extract_variant)]
+/// #[extract_variant(dynamic(serde_json::Value))]
+/// #[derive(Serialize, Deserialize)]
+/// enum Request {
+///     Get { key: String },
+///     Set { key: String, value: i64 },
+/// }
+/// fn main() {
+///     let set = Set::try_from(json!({ "key": "a", "value": 1 })).unwrap();
+///     assert_eq!(Value::from(set), json!({ "key": "a", "value": 1 }));
+///     let request = Request::try_from(json!({ "Get": { "key": "a" } })).unwrap();
+///     assert!(matches!(request, Request::Get { .. }));
+/// }
+/// ```
+///
+/// ## `fixtures`
+/// Generates a `sample()` constructor on each extracted struct, for use in tests. Each field gets
+/// the value given by the `#[sample(...)]` attribute of the variant, or its [Default] otherwise.
+/// With [`kind`](#kind), the enum also gets a `sample_of()` constructor taking the kind to build.
+///
+/// The constructors only exist in tests, or when the `fixtures` feature of your crate is enabled, so
+/// that other crates can use them in their own tests. Declare the feature in your `Cargo.toml`:
+/// ```toml
+/// [features]
+/// fixtures = []
+/// ```
+/// Otherwise, the compiler warns about an unexpected `cfg` condition value. The constructors can
+/// also be put under another `cfg` predicate, e.g. `fixtures(cfg = test)` to only have them in
+/// the tests of your crate, or `fixtures(cfg = feature = "testing")` for a feature of another name.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(fixtures kind)]
+/// enum Request {
+///     #[sample(path = String::from("/index.html"))]
+///     Get { path: String, retries: u8 },
+///     #[sample(0 = 42)]
+///     Cancel(u32),
+/// }
+/// #[cfg(test)]
+/// fn test() {
+///     let get = Get::sample();
+///     let cancel = Request::sample_of(RequestKind::Cancel);
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `chain`
+/// Layered enums, where a variant of an outer enum wraps a whole inner enum, need conversions
+/// between the structs of the inner enum and the outer one. `chain(Outer::Variant)` on the inner
+/// enum gives each of its structs `From` and `TryFrom` conversions with the outer enum, going
+/// through the wrapping variant, and makes it a [`Variant`][variant_traits::Variant] of the outer
+/// enum too, so `into_enum()` then needs to be told which enum to build. Several outer enums can
+/// be given, separated by commas. Cannot be combined with `no_impl` or `wrong_variant`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(Debug)]
+/// enum Frame {
+///     Control(Control),
+///     Data(Vec<u8>),
+/// }
+///
+/// #[derive(extract_variant)]
+/// #[extract_variant(chain(Frame::Control))]
+/// #[derive(Debug)]
+/// enum Control {
+///     Ping,
+///     Close { code: u16 },
+/// }
+/// fn main() {
+///     let frame = Frame::from(Close { code: 1000 });
+///     assert!(matches!(frame, Frame::Control(Control::Close { code: 1000 })));
+///     assert!(Ping::try_from(frame).is_err());
+/// }
+/// ```
+///
+/// ## `sibling_try_from`
+/// Implements [`TryFrom`] between every two extracted structs, going through the enum like
+/// [`convert`][variant_traits::convert()]: the error is the enum the value was wrapped into. This
+/// lets generic code bounded on `TryFrom` move between the variants of a state machine. Cannot be
+/// combined with `no_impl`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(sibling_try_from)]
+/// enum State {
+///     Idle,
+///     Running { pid: u32 },
+/// }
+/// fn main() {
+///     let state: Result<Idle, State> = Idle::try_from(Running { pid: 1 });
+///     assert!(matches!(state, Err(State::Running { pid: 1 })));
+/// }
+/// ```
+///
+/// ## `seal`
+/// Generates a `{Enum}Variant` trait, implemented by the struct of each variant and by no other
+/// type, so that other crates can write functions taking any variant of the enum, while adding a
+/// variant stays up to you. The trait extends [`Variant`][variant_traits::Variant], and a sealed
+/// trait that can't be named, let alone implemented, outside of the generated code. Cannot be
+/// combined with `no_impl` or `wrong_variant`, and leaves out the variants with
+/// `#[fallible_from(...)]`, which don't implement `Variant`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(seal)]
+/// #[derive(Debug)]
+/// pub enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+/// }
+/// pub fn describe(variant: impl MyEnumVariant + std::fmt::Debug) -> String {
+///     let value: MyEnum = variant.into();
+///     format!("{:?}", value)
+/// }
+/// fn main() {
+///     assert_eq!(describe(TupleVariant(1)), "TupleVariant(1)");
+/// }
+/// ```
+///
+/// ## `match_macro`
+/// Generates a `match_{enum}!` macro, named after the enum in `snake_case`, which matches on a value
+/// of the enum with an arm per variant, each binding the extracted struct (or the enum itself for
+/// excluded variants). Unlike a chain of [TryFrom] calls, a missing variant fails to compile. Like
+/// any `macro_rules!` macro, it can be used after the enum in the same module, with the names of
+/// the enum and its structs in scope. Cannot be combined with `no_impl`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(match_macro)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+///     StructVariant { field: f64, },
+/// }
+/// fn main() {
+///     let description = match_my_enum!(MyEnum::TupleVariant(42), {
+///         UnitVariant(_) => String::from("unit"),
+///         TupleVariant(TupleVariant(n)) => n.to_string(),
+///         StructVariant(v) => v.field.to_string(),
+///     });
+/// }
+/// ```
+///
+/// ## `assert_macro`
+/// Generates an `assert_{enum}_variants!` macro, named after the enum in `snake_case`, which fails
+/// to compile unless it's given exactly the variants of the enum. Code that handles each variant
+/// outside of a `match`, such as a table of handlers, can then assert that it's kept up to date:
+/// adding a variant to the enum breaks the assertion until it's added to the list too.
+///
+/// The macro is local to the crate, like [`match_macro`](#match_macro). To export it for other
+/// crates, give the path of the enum from the root of the crate, e.g.
+/// `assert_macro(crate::shapes::Shape)`. It's then available at the root of your crate, as any
+/// `#[macro_export]` macro. Cannot be used on a `#[non_exhaustive]` enum from another crate.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(assert_macro)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+///     StructVariant { field: f64, },
+/// }
+/// assert_my_enum_variants!(UnitVariant, TupleVariant, StructVariant);
+/// # fn main() {}
+/// ```
+///
+/// ## `indices`
+/// Generates a `VARIANT_COUNT` constant on the enum, and an `INDEX` constant on each extracted
+/// struct with the position of its variant in declaration order, e.g. to index per-variant tables.
+/// Excluded variants are counted too, unless given as `indices(extracted)`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(indices(extracted))]
+/// enum MyEnum {
+///     UnitVariant,
+///     #[exclude]
+///     TupleVariant(i32),
+///     StructVariant { field: f64, },
+/// }
+/// fn main() {
+///     let mut counters = [0; MyEnum::VARIANT_COUNT];
+///     counters[StructVariant::INDEX] += 1;
+///     assert_eq!(StructVariant::INDEX, 1);
+/// }
+/// ```
+///
+/// ## `wrap_fields`
+/// Wraps every field of the generated structs in a generic type, e.g. to carry spans the enum
+/// doesn't have. `wrap_fields(Spanned)` gives a field of type `T` the type `Spanned<T>`, built
+/// with `Spanned::from` and unwrapped with `Spanned::into_inner`. Any other type can be given with
+/// `_` where the type of the field goes, e.g. `wrap_fields(Arc<Mutex<_>>)`. Other functions (or
+/// closures) can be given as `wrap = ...` and `unwrap = ...`, including generic paths such as
+/// `convert::unwrap::<Span>`. The wrapper goes around the type given by `#[boxed]`, `#[unboxed]`
+/// or `#[map_type]`, whose types can be any type, e.g. `Vec<Spanned<Expr>>` or
+/// `<T as Trait>::Output`.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// struct Spanned<T> {
+///     value: T,
+///     span: std::ops::Range<usize>,
+/// }
+/// fn unspanned<T>(value: T) -> Spanned<T> {
+///     Spanned { value, span: 0..0 }
+/// }
+///
+/// #[derive(extract_variant)]
+/// #[extract_variant(wrap_fields(Spanned, wrap = unspanned, unwrap = |s: Spanned<_>| s.value))]
+/// enum Node {
+///     Literal(i64),
+///     Binary { op: char, rhs: i64 },
+/// }
+/// fn main() {
+///     let binary = Binary {
+///         op: Spanned { value: '+', span: 2..3 },
+///         rhs: Spanned { value: 1, span: 4..5 },
+///     };
+///     assert!(matches!(Node::from(binary), Node::Binary { op: '+', rhs: 1 }));
+/// }
+/// ```
+///
+/// ## `clap_args`
+/// Derives [`clap::Args`](https://docs.rs/clap/latest/clap/trait.Args.html) on the generated
+/// structs, so that the payload of a subcommand can be parsed on its own and converted into the
+/// enum. The `#[arg(...)]` attributes of the fields are kept, and the doc comments of the variants
+/// become the help of the structs. The crate using the option must depend on `clap` with its
+/// `derive` feature. Tuple variants can't be parsed as arguments and must be excluded.
+/// ```rust, ignore
+/// # use extract_variant::extract_variant;
+/// use clap::{Parser, Subcommand};
+///
+/// #[derive(Subcommand, extract_variant)]
+/// #[extract_variant(clap_args)]
+/// enum Command {
+///     /// Adds a file to the index
+///     Add {
+///         #[arg(short, long)]
+///         force: bool,
+///         path: String,
+///     },
+///     Status,
+/// }
+/// fn main() {
+///     // Only the arguments of `add`
+///     #[derive(Parser)]
+///     struct Cli {
+///         #[command(flatten)]
+///         add: Add,
+///     }
+///     let command = Command::from(Cli::parse().add);
+/// }
+/// ```
+///
+/// ## `wrong_variant`
+/// Makes `TryFrom<MyEnum>` fail with a [`WrongVariant<MyEnum>`][variant_traits::WrongVariant]
+/// instead of the enum itself, which tells the expected variant and the kind that was found
+/// before giving the enum back with `into_inner`. Requires `kind`. The structs then don't
+/// implement [`Variant`][variant_traits::Variant], which relies on the enum being the error, so
+/// `sibling_try_from` can't be combined with it; `HasVariant` is still implemented.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[derive(Debug)]
+/// #[extract_variant(kind wrong_variant)]
+/// enum MyEnum {
+///     UnitVariant,
+///     TupleVariant(i32),
+/// }
+/// fn main() {
+///     let error = TupleVariant::try_from(MyEnum::UnitVariant).unwrap_err();
+///     assert_eq!(error.actual_kind(), MyEnumKind::UnitVariant);
+///     println!("{}", error); // expected variant `TupleVariant`, found `UnitVariant`
+///     let value: MyEnum = error.into_inner();
+/// }
+/// ```
+///
+/// ## `trim_prefix`
+/// Removes the given prefix from the names of the variants when naming their structs, before any
+/// prefix or suffix is added. Variants not starting with it, or whose whole name (or all but a
+/// digit-led rest) it is, keep their names.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(trim_prefix(Token) suffix(Token))]
+/// enum Token {
+///     TokenIdent(String),
+///     TokenNumber(f64),
+///     Eof,
+/// }
+/// fn main() {
+///     let ident = IdentToken(String::from("x"));
+///     let number = NumberToken(1.0);
+///     let eof = EofToken;
+/// }
+/// ```
+///
+/// ## `reserved`
+/// A variant named after a type or trait of the prelude, such as `String` or `Option`, gives a
+/// struct shadowing it in the whole module, which then breaks code far from the enum. The
+/// `reserved(...)` option chooses what to do with such names: `error` rejects them, `suffix` adds
+/// `Variant` to them (e.g. `StringVariant`), and `shadow` keeps them, which is the default. The
+/// name is checked after adding the prefix and suffix.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(reserved(suffix))]
+/// enum Value {
+///     String(String),
+///     Number(f64),
+/// }
+/// fn main() {
+///     let value: Value = StringVariant(String::from("hello")).into();
+///     let number = Number(1.0);
+/// }
+/// ```
+///
+/// ## `order`
+/// Chooses the order of the variants in the lists generated from them: the
+/// [`Variants`][variant_traits::Variants] tuple, the variants of the exploded enum, the entries of
+/// the registry and the methods of the handler trait. `order(declaration)`, the default, keeps the
+/// order of the enum, and `order(alphabetical)` sorts them by the names of the variants, so that
+/// reordering the enum doesn't reorder anything derived from these lists, e.g. a serialized
+/// exploded enum. What mirrors the enum itself, such as the kind enum, its discriminants and
+/// `INDEX`, always follows declaration order.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(exploded order(alphabetical))]
+/// enum Event {
+///     Stop,
+///     Start(u64),
+/// }
+/// fn main() {
+///     let list: <Event as variant_traits::Variants>::List = (Start(0), Stop);
+/// }
+/// ```
+///
+/// ## `defaults`
+/// Applies the settings declared with [`variant_defaults!`](variant_defaults!) earlier in the
+/// module, or in one of its parents, before the attributes of the enum.
+/// ```rust, no_run
+/// # use extract_variant::{extract_variant, variant_defaults};
+/// variant_defaults! {
+///     #![extract_variant(kind)]
+/// }
+///
+/// #[derive(extract_variant)]
+/// #[extract_variant(defaults)]
+/// enum Event {
+///     Stop,
+///     Start(u64),
+/// }
+/// fn main() {
+///     assert_eq!(Event::from(Stop).kind(), EventKind::Stop);
+/// }
+/// ```
+///
+/// ## `allow`
+/// Allows the given lints on everything the macro generates, and nothing else, for crates that
+/// deny lints the generated code may trip.
+/// ```rust, no_run
+/// #![deny(missing_docs)]
+/// //! A crate documenting everything.
+/// # use extract_variant::extract_variant;
+/// /// An enum.
+/// #[derive(extract_variant)]
+/// #[extract_variant(allow(missing_docs, clippy::large_enum_variant) exploded)]
+/// pub enum MyEnum {
+///     /// A variant.
+///     Small(u8),
+///     /// Another variant.
+///     Large([u8; 1024]),
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `allow_unused`
+/// Allows `dead_code` on everything the macro generates, for binary crates where some of the
+/// structs or conversions aren't used yet. It is off by default, so that a library still hears
+/// about the variants nothing converts to.
+/// ```rust, no_run
+/// #![deny(dead_code)]
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(allow_unused to_getters)]
+/// enum Command {
+///     Run(String),
+///     Stop,
+/// }
+/// fn main() {
+///     let _ = Command::Stop;
+/// }
+/// ```
+///
+/// ## `field_origin_docs`
+/// Documents each field of the generated structs with the field of the variant it comes from, e.g.
+/// "Corresponds to `MyEnum::StructVariant.field`", after the field's own docs. The docs of the
+/// fields are kept either way, whatever `#[tuple]`, `#[map_type]` or the other field attributes
+/// do to the fields.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(field_origin_docs)]
+/// enum MyEnum {
+///     StructVariant {
+///         /// The field.
+///         field: f64,
+///     },
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `vis_from_fields`
+/// The fields of the generated structs are public, which fails to compile (or warns) when the
+/// type of a field is less visible than the enum. A field can be given another visibility with
+/// `#[vis(...)]`, e.g. `#[vis(pub(crate))]`, or `#[vis()]` for a private one. With this option,
+/// each struct is then no more visible than the narrowest of them, and the enum no longer
+/// implements [`Variants`][variant_traits::Variants] if that makes any struct less visible than
+/// itself.
+/// ```rust, no_run
+/// # use extract_variant::extract_variant;
+/// pub(crate) struct Internal;
+///
+/// #[derive(extract_variant)]
+/// #[extract_variant(vis_from_fields)]
+/// pub enum MyEnum {
+///     Public(u8),
+///     Restricted {
+///         #[vis(pub(crate))]
+///         internal: Internal,
+///     },
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `strict`
+/// Makes the helper attributes that would otherwise be ignored compile errors: `#[prefix]`,
+/// `#[suffix]` or `#[no_impl]` on a variant, the variant and field attributes on the enum, the
+/// attributes configuring the struct of a variant that doesn't get one, e.g. `#[variant_derive]`
+/// on an `#[exclude]`d variant, and `#[kind_name]` on an `#[exclude(all)]` one.
+/// ```rust, compile_fail
+/// # use extract_variant::extract_variant;
+/// #[derive(extract_variant)]
+/// #[extract_variant(strict)]
+/// enum MyEnum {
+///     #[exclude]
+///     #[variant_derive(Default)]
+///     UnitVariant,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// # Limitations
+/// Currently, `extract_variant` does not support extracting variants from enums with
+/// generic parameters or lifetime parameters. It can only extract variants from enums
+/// that are monomorphic.
+///
+/// `Self` in the type of a field stands for the enum, e.g. in `Array(Vec<Self>)`, and is replaced
+/// with the enum in the generated struct, where it would stand for the struct instead.
+///
+/// Before generating anything, the macro checks that no two of its features declare the same type
+/// or implement the same conversion, e.g. a struct named like the enum or the kind enum, or
+/// `chain` and `dynamic` both converting the structs into the same type, and reports it as an
+/// error. Types are compared by how they're written, so the same type written as two different
+/// paths isn't caught.
+///
+/// An enum without variants expands to nothing, whatever its options. Variants holding uninhabited
+/// types such as [`Infallible`][std::convert::Infallible] are extracted like any other.
+///
+/// The kind and exploded enums of a `#[non_exhaustive]` enum are `#[non_exhaustive]` as well, so
+/// that other crates are ready for the variants it may gain.
+///
+/// # TODO
+/// Document `#[exclude]` attribute. Just put it on any variant that shouldn't be extracted.
+///
+#[proc_macro_derive(
+    extract_variant,
+    attributes(
+        extract_variant,
+        prefix,
+        suffix,
+        no_impl,
+        variant_attrs,
+        variant_derive,
+        impl_attrs,
+        fallible_from,
+        display_skip,
+        display_fmt,
+        exclude,
+        boxed,
+        unboxed,
+        map_type,
+        tuple,
+        into_iterator,
+        code,
+        tag,
+        sample,
+        kind_name,
+        accessor_name,
+        doc_inherit,
+        no_doc_inherit,
+        vis
+    )
+)]
+pub fn extract_variant(input: TokenStream) -> TokenStream {
+    extract_variant_core::extract_variant(input.into()).into()
+}
+
+/// Extracts the variants of an enum defined elsewhere, e.g. in another crate, into standalone
+/// structs.
+///
+/// Since the enum can't be derived on, its shape is restated in the macro: its path, followed by
+/// its variants as they would be written in its declaration. The structs and their conversions
+/// are generated as with [`extract_variant`][extract_variant()], with the enum-level attributes
+/// (which go before the path, along with the visibility of the structs) and the variant-level ones.
+/// Variants marked `#[exclude]` are still checked but don't get a struct.
+///
+/// Nothing is implemented on the enum itself, as it may belong to another crate, so the options
+/// adding methods or traits to it are rejected: `kind`, `to_getters`, `as_getters`,
+/// `unchecked_getters`, `display`, `handler`, `fixtures`, `indices` and `#[code(...)]`.
+///
+/// The restated variants are checked against the actual enum at compile time: the conversions
+/// check the fields of each extracted variant, and a variant missing from the list is a
+/// non-exhaustive match error. For `#[non_exhaustive]` enums, end the list with `..` or put
+/// `#[non_exhaustive]` before the path. The options needing every variant (`exploded`,
+/// `match_macro` and `assert_macro`) are then rejected.
+///
+/// A macro can't tell which `#[cfg_attr(...)]` predicates hold, so each combination of the ones
+/// before the path is expanded under a `#[cfg(...)]` of its own, which allows at most four of them.
+///
+/// # Example
+/// ```rust, no_run
+/// use extract_variant::extract_variants_of;
+///
+/// mod json {
+///     pub enum Value {
+///         Null,
+///         Bool(bool),
+///         Number(f64),
+///         String(String),
+///         Array(Vec<Value>),
+///     }
+/// }
+///
+/// extract_variants_of! {
+///     #[derive(Debug, Clone, PartialEq)]
+///     #[prefix(Json)]
+///     pub json::Value {
+///         Null,
+///         Bool(bool),
+///         Number(f64),
+///         String(String),
+///         #[exclude]
+///         Array(Vec<json::Value>),
+///     }
+/// }
+///
+/// fn main() {
+///     let value: json::Value = JsonNumber(1.0).into();
+///     assert_eq!(JsonNumber::try_from(value).ok(), Some(JsonNumber(1.0)));
+/// }
+/// ```
+#[proc_macro]
+pub fn extract_variants_of(input: TokenStream) -> TokenStream {
+    extract_variant_core::extract_variants_of(input.into()).into()
+}
+
+/// Declares several enums at once, each derived with [`extract_variant`][extract_variant()] with
+/// the same settings, e.g. for a family of message enums extracted the same way.
+///
+/// The block starts with the attributes shared by every enum, written as inner attributes
+/// (`#![...]`), followed by the enums. The shared attributes go before the own attributes of each
+/// enum, so shared derives are inherited by the structs too, and the options of both
+/// `#[extract_variant(...)]` attributes are merged. `Self` in the shared attributes stands for the
+/// name of each enum, e.g. `#![prefix(Self)]` prefixes the structs of each enum with its name. To
+/// put the enums in a module, invoke the macro in it.
+///
+/// # Example
+/// ```rust, no_run
+/// use extract_variant::extract_variants;
+///
+/// extract_variants! {
+///     #![extract_variant(kind)]
+///     #![prefix(Self)]
+///     #![derive(Debug, Clone, PartialEq)]
+///
+///     pub enum Request {
+///         Get { key: String },
+///         Stop,
+///     }
+///
+///     #[extract_variant(exploded)]
+///     pub enum Response {
+///         Value(String),
+///         Stop,
+///     }
+/// }
+///
+/// fn main() {
+///     assert_eq!(Request::from(RequestStop).kind(), RequestKind::Stop);
+///     assert_eq!(ResponseStop::try_from(Response::Stop), Ok(ResponseStop));
+/// }
+/// ```
+#[proc_macro]
+pub fn extract_variants(input: TokenStream) -> TokenStream {
+    extract_variant_core::extract_variants(input.into()).into()
+}
+
+/// Declares settings for every enum of the module deriving [`extract_variant`][extract_variant()]
+/// with the [`defaults`](extract_variant()#defaults) option, so that a codebase can extract its
+/// enums the same way without repeating the same attributes on each of them.
+///
+/// The settings are written as in [`extract_variants!`], as inner attributes (`#![...]`) where
+/// `Self` stands for the name of each enum. They apply to the enums declared after the macro, in
+/// its module and the modules declared in it. The options of both `#[extract_variant(...)]`
+/// attributes are merged, and shared derives are given to the structs, as the enum is already
+/// declared. Any other setting is overridden by an attribute of the same name on the enum.
+///
+/// # Example
+/// ```rust, no_run
+/// use extract_variant::{extract_variant, variant_defaults};
+///
+/// variant_defaults! {
+///     #![extract_variant(kind to_getters)]
+///     #![prefix(Self)]
+///     #![derive(Debug, Clone)]
+/// }
+///
+/// #[derive(extract_variant, Debug, Clone)]
+/// #[extract_variant(defaults)]
+/// pub enum Request {
+///     Get { key: String },
+///     Stop,
+/// }
+///
+/// #[derive(extract_variant)]
+/// #[extract_variant(defaults)]
+/// #[prefix(Reply)]
+/// pub enum Response {
+///     Value(String),
+///     Stop,
+/// }
+///
+/// fn main() {
+///     assert_eq!(Request::from(RequestStop).kind(), RequestKind::Stop);
+///     let _stop: ReplyStop = ReplyStop::try_from(Response::Stop).ok().unwrap().clone();
+/// }
+/// ```
+#[proc_macro]
+pub fn variant_defaults(input: TokenStream) -> TokenStream {
+    extract_variant_core::variant_defaults(input.into()).into()
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __apply_variant_defaults(input: TokenStream) -> TokenStream {
+    extract_variant_core::apply_variant_defaults(input.into()).into()
+}
+
+/// Checks at compile time that a struct has the same fields as a variant of an enum, as stated in
+/// the macro, e.g. for a struct converted by hand or with [`Variant`][derive_variant()] from a
+/// variant of an enum in another crate.
+///
+/// The macro takes the path of the struct, then the path of the variant followed by its fields, as
+/// they would be written in the declaration of the enum. The fields of both the struct and the
+/// variant must then have exactly the stated names (or count, for tuple variants) and types, so
+/// that either of them drifting away from the other is a compile error. The fields of the struct
+/// must be visible where the macro is invoked.
+///
+/// # Example
+/// ```rust, no_run
+/// use extract_variant::assert_variant_shape;
+///
+/// mod my_mod {
+///     pub enum MyEnum {
+///         StructVariant { field: f64 },
+///         TupleVariant(i32, String),
+///     }
+/// }
+///
+/// struct MyStruct {
+///     field: f64,
+/// }
+/// struct MyTuple(i32, String);
+///
+/// assert_variant_shape!(MyStruct, my_mod::MyEnum::StructVariant { field: f64 });
+/// assert_variant_shape!(MyTuple, my_mod::MyEnum::TupleVariant(i32, String));
+/// # fn main() {}
+/// ```
+#[proc_macro]
+pub fn assert_variant_shape(input: TokenStream) -> TokenStream {
+    extract_variant_core::assert_variant_shape(input.into()).into()
+}
+
+/// TODO
+///
+/// In some cases, you may want to manually create a struct that corresponds to a variant of an enum,
+/// without using [`extract_variant`][extract_variant()] to extract the variant automatically.
+/// This can be useful if you want to create a struct that corresponds to a variant of an enum
+/// that cannot be extracted by [`extract_variant`][extract_variant()] (for example,
+/// because it is defined in another crate or has generic parameters).
+///
+/// # Example
+/// ```rust, no_run
+/// use extract_variant::Variant;
+///
+/// mod my_mod {
+///     pub enum MyEnum {
+///         UnitVariant,
+///         TupleVariant(i32),
+///         StructVariant { field: f64, },
+///     }   
+/// }
+///
+/// #[derive(Variant)]
+/// #[variant_of(my_mod::MyEnum)]
+/// struct UnitVariant;
+///
+/// #[derive(Variant)]
+/// #[variant_of(my_mod::MyEnum)]
+/// struct TupleVariant(i32);
+///
+/// #[derive(Variant)]
+/// #[variant_of(my_mod::MyEnum)]
+/// struct StructVariant {
+///     field: f64,
+/// }
+/// ```
+///
+/// # Non-strict conversions
+/// The variant of an enum from another crate may be hidden, or renamed from one version to the
+/// next, especially if the enum is `#[non_exhaustive]`. With `strict = false`, the conversions
+/// don't name the variant at all, and call the given `into` (from the struct to the enum) and
+/// `from` (from the enum to the struct, giving the enum back on failure) functions instead.
+/// ```rust, no_run
+/// use extract_variant::Variant;
+///
+/// mod other_crate {
+///     #[non_exhaustive]
+///     pub enum Level {
+///         Info,
+///         Warning,
+///     }
+/// }
+/// use other_crate::Level;
+///
+/// fn is_info(level: &Level) -> bool {
+///     matches!(level, Level::Info)
+/// }
+///
+/// #[derive(Variant)]
+/// #[variant_of(
+///     Level,
+///     strict = false,
+///     into = |_| Level::Info,
+///     from = |level| if is_info(&level) { Ok(Info) } else { Err(level) },
+/// )]
+/// struct Info;
+/// ```
+///
+/// # Boxed variants
+/// A recursive enum usually holds its data in a `Box`, e.g. `Node(Box<NodeData>)`. With `boxed`,
+/// the struct is the one in the box: it is boxed when converted into the enum, and unboxed when
+/// extracted from it, so that it never has to be boxed itself.
+/// ```rust, no_run
+/// use extract_variant::Variant;
+///
+/// enum Tree {
+///     Leaf(u32),
+///     Node(Box<NodeData>),
+/// }
+///
+/// #[derive(Variant)]
+/// #[variant_of(Tree, Node, boxed)]
+/// struct NodeData {
+///     left: Tree,
+///     right: Tree,
+/// }
+/// ```
+///
+/// # Transparent structs
+/// A struct with a single field can also stand for a single-field variant whose field has another
+/// type, with `transparent`: the field of the struct is converted into the field of the variant with
+/// [Into] on the way into the enum, and back on the way out, e.g. for a wrapper that validates or
+/// tags the type used in the enum.
+/// ```rust, no_run
+/// use extract_variant::Variant;
+///
+/// enum Value {
+///     Number(f64),
+///     Text(String),
+/// }
+///
+/// struct Meters(f64);
+///
+/// impl From<f64> for Meters {
+///     fn from(value: f64) -> Self {
+///         Meters(value)
+///     }
+/// }
+///
+/// impl From<Meters> for f64 {
+///     fn from(Meters(value): Meters) -> Self {
+///         value
+///     }
+/// }
+///
+/// #[derive(Variant)]
+/// #[variant_of(Value, Number, transparent)]
+/// struct Distance(Meters);
+/// ```
+///
+/// # Discriminants
+/// A unit struct standing for a C-like variant can carry its discriminant, as a `DISCRIMINANT`
+/// constant, e.g. to keep FFI constants next to their marker types. The macro can't see the enum,
+/// so the discriminant is given as `discriminant = ...`, along with the `#[repr(...)]` of the enum
+/// as `repr = ...` unless it's the default `isize`. It's checked against the variant at compile
+/// time, except with `strict = false`, as the variant isn't named then.
+/// ```rust, no_run
+/// use extract_variant::Variant;
+///
+/// #[repr(u8)]
+/// enum Signal {
+///     Hangup = 1,
+///     Interrupt = 2,
+/// }
+///
+/// #[derive(Variant)]
+/// #[variant_of(Signal, Interrupt, discriminant = 2, repr = u8)]
+/// struct Interrupt;
+///
+/// fn main() {
+///     assert_eq!(Interrupt::DISCRIMINANT, Signal::Interrupt as u8);
+/// }
+/// ```
+///
+/// # Helpers
+/// An enum from another crate can't be given methods, so `helpers` implements functions on the
+/// struct instead: `matches(&Enum)` tells whether the enum holds the variant, and `extract_ref(&Enum)`
+/// borrows its fields, as a tuple unless there is only one, or the struct itself with `boxed`.
+/// `extract_ref` isn't implemented for unit structs, nor with `transparent`, as the type of the
+/// field of the variant isn't known then.
+/// ```rust, no_run
+/// use extract_variant::Variant;
+///
+/// enum Shape {
+///     Circle { radius: f64 },
+///     Rectangle(f64, f64),
+/// }
+///
+/// #[derive(Variant)]
+/// #[variant_of(Shape, helpers)]
+/// struct Rectangle(f64, f64);
+///
+/// fn main() {
+///     let shape = Shape::Rectangle(1.0, 2.0);
+///     assert!(Rectangle::matches(&shape));
+///     assert_eq!(Rectangle::extract_ref(&shape), Some((&1.0, &2.0)));
+/// }
+/// ```
+///
+/// # Marker only
+/// When the conversions are already written by hand, `marker_only` only implements the
+/// [`Variant`][variant_traits::Variant] marker on top of them. A missing or mismatched conversion
+/// (`TryFrom` must give the enum back as its error) is reported on the `marker_only` flag.
+/// ```rust, no_run
+/// use extract_variant::Variant;
+///
+/// enum Shape {
+///     Circle(f64),
+///     Square(f64),
+/// }
+///
+/// #[derive(Variant)]
+/// #[variant_of(Shape, Circle, marker_only)]
+/// struct Circle {
+///     radius: f64,
+/// }
+///
+/// impl From<Circle> for Shape {
+///     fn from(circle: Circle) -> Self {
+///         Shape::Circle(circle.radius)
+///     }
+/// }
+///
+/// impl TryFrom<Shape> for Circle {
+///     type Error = Shape;
+///     fn try_from(shape: Shape) -> Result<Self, Shape> {
+///         match shape {
+///             Shape::Circle(radius) => Ok(Circle { radius }),
+///             shape => Err(shape),
+///         }
+///     }
+/// }
+/// ```
+#[proc_macro_derive(Variant, attributes(variant_of))]
+pub fn derive_variant(input: TokenStream) -> TokenStream {
+    extract_variant_core::derive_variant(input.into()).into()
 }
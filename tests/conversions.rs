@@ -0,0 +1,772 @@
+use extract_variant::extract_variant;
+
+/// Every shape of variant, with every way of changing the shape of the struct.
+mod shapes {
+    use super::*;
+
+    #[derive(extract_variant)]
+    #[extract_variant(to_getters)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Shape {
+        Unit,
+        Tuple(u8, String),
+        Named {
+            first: u8,
+            second: String,
+        },
+        #[tuple]
+        Flattened {
+            first: u8,
+            second: String,
+        },
+        Boxed {
+            #[boxed]
+            first: u8,
+            second: String,
+        },
+        #[map_type(1: String => Box<str>)]
+        Mapped(u8, String),
+        #[tuple]
+        #[map_type(second: Vec<u8> => Box<[u8]>)]
+        MappedFlattened {
+            first: String,
+            second: Vec<u8>,
+        },
+    }
+
+    fn round_trip<S>(value: Shape) -> S
+    where
+        S: TryFrom<Shape, Error = Shape> + Into<Shape>,
+    {
+        let variant = S::try_from(value.clone()).unwrap_or_else(|_| panic!("wrong variant"));
+        let back: Shape = S::try_from(value.clone()).ok().unwrap().into();
+        assert_eq!(back, value);
+        variant
+    }
+
+    #[test]
+    fn unit() {
+        let Unit = round_trip::<Unit>(Shape::Unit);
+        const DEFAULT: Shape = Unit::VALUE;
+        assert_eq!(DEFAULT, Shape::Unit);
+    }
+
+    #[test]
+    fn tuple() {
+        let Tuple(first, second) = round_trip(Shape::Tuple(1, "a".into()));
+        assert_eq!((first, second.as_str()), (1, "a"));
+    }
+
+    #[test]
+    fn named() {
+        let Named { second, first } = round_trip(Shape::Named {
+            first: 1,
+            second: "a".into(),
+        });
+        assert_eq!((first, second.as_str()), (1, "a"));
+    }
+
+    #[test]
+    fn named_into_tuple() {
+        let Flattened(first, second) = round_trip(Shape::Flattened {
+            first: 1,
+            second: "a".into(),
+        });
+        assert_eq!((first, second.as_str()), (1, "a"));
+    }
+
+    #[test]
+    fn boxed() {
+        let Boxed { first, second } = round_trip(Shape::Boxed {
+            first: 1,
+            second: "a".into(),
+        });
+        assert_eq!((*first, second.as_str()), (1, "a"));
+        let boxed = Shape::Boxed {
+            first: 2,
+            second: "b".into(),
+        }
+        .to_boxed()
+        .unwrap();
+        assert_eq!(*boxed.first, 2);
+    }
+
+    #[test]
+    fn mapped() {
+        let Mapped(first, second) = round_trip(Shape::Mapped(1, "a".into()));
+        assert_eq!((first, &*second), (1, "a"));
+    }
+
+    #[test]
+    fn mapped_into_tuple() {
+        let MappedFlattened(first, second) = round_trip(Shape::MappedFlattened {
+            first: "a".into(),
+            second: vec![1],
+        });
+        assert_eq!((first.as_str(), &*second), ("a", &[1u8][..]));
+    }
+
+    #[test]
+    fn wrong_variant() {
+        assert_eq!(Named::try_from(Shape::Unit), Err(Shape::Unit));
+        assert_eq!(Unit::try_from(Shape::Tuple(1, "a".into())).ok(), None);
+    }
+}
+
+/// Renamed structs, built from their fields in any order.
+mod renames {
+    use super::*;
+
+    #[derive(extract_variant)]
+    #[extract_variant(prefix(Msg) suffix(Body))]
+    #[derive(Debug, PartialEq)]
+    pub enum Message {
+        Quit,
+        Move(i32, i32),
+        Write { text: String, urgent: bool },
+    }
+
+    #[test]
+    fn renamed_structs_convert() {
+        assert_eq!(Message::from(MsgQuitBody), Message::Quit);
+        assert_eq!(Message::from(MsgMoveBody(1, 2)), Message::Move(1, 2));
+        let write = MsgWriteBody {
+            urgent: true,
+            text: "hi".into(),
+        };
+        assert_eq!(
+            Message::from(write),
+            Message::Write {
+                text: "hi".into(),
+                urgent: true
+            }
+        );
+        let MsgWriteBody { urgent, text } = Message::Write {
+            text: "hi".into(),
+            urgent: false,
+        }
+        .try_into()
+        .unwrap();
+        assert_eq!((text.as_str(), urgent), ("hi", false));
+    }
+}
+
+/// Structs cloned out of an enum shared behind an `Arc`.
+mod shared {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(extract_variant)]
+    #[extract_variant(from_arc)]
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Joined {
+            #[boxed]
+            name: String,
+        },
+        Moved(i32, i32),
+        Left,
+    }
+
+    #[test]
+    fn variants_are_cloned_out_of_the_arc() {
+        let event = Arc::new(Event::Joined {
+            name: "ferris".into(),
+        });
+        let Joined { name } = Joined::try_from(event.clone()).unwrap();
+        assert_eq!(*name, "ferris");
+        assert_eq!(
+            Moved::try_from(Arc::new(Event::Moved(1, 2))).ok(),
+            Some(Moved(1, 2))
+        );
+        let error = Left::try_from(event.clone()).unwrap_err();
+        assert!(Arc::ptr_eq(&error, &event));
+    }
+}
+
+/// Structs checked by a validator before they are wrapped into the enum.
+mod validated {
+    use super::*;
+
+    #[derive(extract_variant)]
+    #[extract_variant]
+    #[derive(Debug, PartialEq)]
+    pub enum Range {
+        #[fallible_from(validator = check_bounds, error = String)]
+        Bounded {
+            low: u32,
+            high: u32,
+        },
+        Unbounded,
+    }
+
+    fn check_bounds(bounded: &Bounded) -> Result<(), String> {
+        if bounded.low <= bounded.high {
+            Ok(())
+        } else {
+            Err("inverted bounds".into())
+        }
+    }
+
+    #[test]
+    fn invalid_structs_are_rejected() {
+        assert_eq!(
+            Range::try_from(Bounded { low: 1, high: 2 }),
+            Ok(Range::Bounded { low: 1, high: 2 })
+        );
+        assert_eq!(
+            Range::try_from(Bounded { low: 3, high: 2 }),
+            Err("inverted bounds".to_owned())
+        );
+        assert_eq!(
+            Bounded::try_from(Range::Bounded { low: 3, high: 4 }),
+            Ok(Bounded { low: 3, high: 4 })
+        );
+        assert_eq!(Range::from(Unbounded), Range::Unbounded);
+    }
+}
+
+/// Structs with private fields, only reachable through their accessors.
+mod readonly {
+    mod shapes {
+        use extract_variant::extract_variant;
+
+        #[derive(extract_variant)]
+        #[extract_variant(readonly hygienic)]
+        #[derive(Debug, PartialEq)]
+        pub enum Shape {
+            Circle { radius: f64 },
+            Rectangle(f64, f64),
+        }
+    }
+    use shapes::{Circle, Rectangle, Shape};
+
+    #[test]
+    fn fields_are_reached_through_accessors() {
+        let mut circle = Circle::try_from(Shape::Circle { radius: 1.0 }).unwrap();
+        *circle.radius_mut() *= 2.0;
+        assert_eq!(*circle.radius(), 2.0);
+        assert_eq!(Shape::from(circle), Shape::Circle { radius: 2.0 });
+        let mut rectangle = Rectangle::try_from(Shape::Rectangle(1.0, 2.0)).unwrap();
+        *rectangle._1_mut() = 3.0;
+        assert_eq!(*rectangle._0(), 1.0);
+        assert_eq!(rectangle.into_parts(), (1.0, 3.0));
+    }
+}
+
+mod borrowed {
+    use extract_variant::extract_variant;
+
+    #[derive(extract_variant)]
+    #[extract_variant(as_getters report_sizes)]
+    #[derive(Debug, PartialEq)]
+    pub enum Message {
+        Quit,
+        Write(String),
+        Move {
+            x: i32,
+            y: i32,
+        },
+        #[exclude]
+        Raw(Vec<u8>, usize),
+    }
+
+    #[test]
+    fn fields_are_borrowed_from_the_enum() {
+        let mut message = Message::Move { x: 1, y: 2 };
+        assert_eq!(message.as_move(), Some((&1, &2)));
+        if let Some((x, _)) = message.as_move_mut() {
+            *x = 3;
+        }
+        assert_eq!(message, Message::Move { x: 3, y: 2 });
+        assert_eq!(message.as_write(), None);
+        let write = Message::Write("hello".to_owned());
+        assert_eq!(write.as_write().map(String::as_str), Some("hello"));
+        let raw = Message::Raw(vec![1], 1);
+        assert_eq!(raw.as_raw(), Some((&vec![1], &1)));
+    }
+}
+
+/// Enums declared in a function body.
+mod local {
+    use super::*;
+
+    #[test]
+    fn local_enums_are_extracted() {
+        #[derive(extract_variant)]
+        #[extract_variant(local kind exploded to_getters)]
+        #[derive(Debug, Clone, PartialEq)]
+        enum Step {
+            Forward(u32),
+            Turn { left: bool },
+            Stop,
+        }
+        let step: Step = Forward(3).into();
+        assert_eq!(step.kind(), StepKind::Forward);
+        assert_eq!(step.to_forward(), Some(Forward(3)));
+        assert_eq!(Turn::try_from(Step::Stop), Err(Step::Stop));
+        assert!(matches!(
+            StepExploded::from(Step::Turn { left: true }),
+            StepExploded::Turn(Turn { left: true })
+        ));
+    }
+}
+
+/// `Display` on the enum and the structs, from the same format.
+mod display {
+    use super::*;
+
+    /// Not `Clone`, as the enum is formatted by reference.
+    pub struct Path(String);
+
+    impl std::fmt::Display for Path {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    #[derive(extract_variant)]
+    #[extract_variant(display)]
+    pub enum Error {
+        #[display_fmt("{path} not found")]
+        NotFound {
+            path: Path,
+        },
+        #[display_fmt("io: {_0}")]
+        Io(#[boxed] String),
+        #[display_skip]
+        Timeout,
+        Closed,
+        #[exclude]
+        Other,
+    }
+
+    #[test]
+    fn variants_are_displayed_by_their_struct() {
+        let error = Error::NotFound {
+            path: Path("a.txt".into()),
+        };
+        assert_eq!(error.to_string(), "a.txt not found");
+        let not_found = NotFound {
+            path: Path("b.txt".into()),
+        };
+        assert_eq!(not_found.to_string(), "b.txt not found");
+        assert_eq!(Error::Io("closed".into()).to_string(), "io: closed");
+        assert_eq!(Io(Box::new("closed".into())).to_string(), "io: closed");
+        assert_eq!(Error::Closed.to_string(), "Closed");
+        assert_eq!(Error::Timeout.to_string(), "Timeout");
+        assert_eq!(Error::Other.to_string(), "Other");
+    }
+}
+
+/// Structs and the enum converted through `serde_json::Value`.
+mod dynamic {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+
+    #[derive(extract_variant)]
+    #[extract_variant(dynamic(serde_json::Value))]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub enum Request {
+        Get { key: String },
+        Set { key: String, value: i64 },
+        Ping,
+    }
+
+    #[test]
+    fn values_round_trip_through_json() {
+        let set = Set::try_from(json!({ "key": "a", "value": 1 })).unwrap();
+        assert_eq!(
+            set,
+            Set {
+                key: "a".into(),
+                value: 1
+            }
+        );
+        assert_eq!(Value::from(set), json!({ "key": "a", "value": 1 }));
+        assert_eq!(Value::from(Ping), Value::Null);
+        assert!(Get::try_from(json!({ "value": 1 })).is_err());
+        let request = Request::try_from(json!({ "Get": { "key": "a" } })).unwrap();
+        assert_eq!(request, Request::Get { key: "a".into() });
+        assert_eq!(Value::from(Request::Ping), json!("Ping"));
+    }
+}
+
+/// Structs taken through the sealed trait of their enum.
+mod sealed {
+    use super::*;
+
+    #[derive(extract_variant)]
+    #[extract_variant(seal hygienic)]
+    #[derive(Debug, PartialEq)]
+    pub enum Command {
+        Start { delay: u32 },
+        Stop,
+    }
+
+    fn run(command: impl CommandVariant) -> Command {
+        command.into_enum()
+    }
+
+    #[test]
+    fn variants_implement_the_sealed_trait() {
+        assert_eq!(run(Start { delay: 1 }), Command::Start { delay: 1 });
+        assert_eq!(run(Stop), Command::Stop);
+    }
+}
+
+/// Variants wrapped and filtered in bulk.
+mod iterators {
+    use super::*;
+    use variant_traits::VariantIterExt;
+
+    #[derive(extract_variant)]
+    #[extract_variant]
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Click { x: i32 },
+        Key(char),
+    }
+
+    #[test]
+    fn variants_are_collected_and_filtered() {
+        let events: Vec<Event> = vec![Click { x: 1 }, Click { x: 2 }]
+            .into_iter()
+            .collect_into_enum();
+        assert_eq!(events, [Event::Click { x: 1 }, Event::Click { x: 2 }]);
+        let events = vec![Event::Key('a'), Event::Click { x: 3 }, Event::Key('b')];
+        let keys: String = events
+            .into_iter()
+            .filter_variants::<Key>()
+            .map(|Key(key)| key)
+            .collect();
+        assert_eq!(keys, "ab");
+    }
+}
+
+/// Recursive enums naming themselves as `Self`.
+mod recursive {
+    use super::*;
+
+    #[derive(extract_variant)]
+    #[extract_variant]
+    #[derive(Debug, PartialEq)]
+    pub enum Json {
+        Null,
+        Array(Vec<Self>),
+        Object { entries: Vec<(String, Box<Self>)> },
+    }
+
+    #[test]
+    fn self_stands_for_the_enum() {
+        let Array(items) = Array::try_from(Json::Array(vec![Json::Null])).unwrap();
+        assert_eq!(items, [Json::Null]);
+        let object = Object {
+            entries: vec![("a".into(), Box::new(Json::Null))],
+        };
+        assert!(matches!(Json::from(object), Json::Object { .. }));
+    }
+}
+
+/// Enums declared together with shared settings.
+mod batch {
+    use extract_variant::extract_variants;
+
+    extract_variants! {
+        #![extract_variant(kind)]
+        #![suffix(Self)]
+        #![derive(Debug, PartialEq)]
+
+        pub enum Request {
+            Get { key: String },
+            Stop,
+        }
+
+        #[extract_variant(to_getters)]
+        #[derive(Clone)]
+        pub enum Response {
+            Value(String),
+            Stop,
+        }
+    }
+
+    #[test]
+    fn shared_settings_apply_to_each_enum() {
+        assert_eq!(Request::from(StopRequest).kind(), RequestKind::Stop);
+        let response = Response::Value("a".into());
+        assert_eq!(response.kind(), ResponseKind::Value);
+        assert_eq!(response.to_value(), Some(ValueResponse("a".into())));
+        assert_eq!(StopResponse::try_from(Response::Stop), Ok(StopResponse));
+        assert_eq!(GetRequest::try_from(Request::Stop).ok(), None::<GetRequest>);
+    }
+}
+
+/// Structs of an inner enum converted from and into the outer enum wrapping it.
+mod chained {
+    use super::*;
+    use variant_traits::Variant;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Frame {
+        Control(Control),
+        Data(Vec<u8>),
+    }
+
+    #[derive(extract_variant)]
+    #[extract_variant(chain(Frame::Control))]
+    #[derive(Debug, PartialEq)]
+    pub enum Control {
+        Ping,
+        Close { code: u16 },
+    }
+
+    #[test]
+    fn structs_convert_through_the_inner_enum() {
+        assert_eq!(Frame::from(Ping), Frame::Control(Control::Ping));
+        let frame: Frame = Close { code: 1000 }.into_enum();
+        assert_eq!(Close::try_from(frame), Ok(Close { code: 1000 }));
+        assert_eq!(
+            Ping::try_from(Frame::Control(Control::Close { code: 1 })),
+            Err(Frame::Control(Control::Close { code: 1 }))
+        );
+        assert_eq!(
+            Ping::try_from(Frame::Data(vec![1])),
+            Err(Frame::Data(vec![1]))
+        );
+    }
+}
+
+/// Structs debugged as their variant of the enum.
+mod qualified_debug {
+    use super::*;
+
+    #[derive(extract_variant)]
+    #[extract_variant(qualified_debug)]
+    #[derive(Debug, Clone)]
+    pub enum Event {
+        Started,
+        Moved(i32, i32),
+        Renamed {
+            #[boxed]
+            name: String,
+        },
+    }
+
+    #[test]
+    fn structs_mention_the_enum() {
+        assert_eq!(format!("{:?}", Started), "Event::Started");
+        assert_eq!(format!("{:?}", Moved(1, 2)), "Event::Moved(1, 2)");
+        let renamed = Renamed {
+            name: Box::new("a".into()),
+        };
+        assert_eq!(format!("{:?}", renamed), r#"Event::Renamed { name: "a" }"#);
+        assert_eq!(format!("{:?}", Event::Started), "Started");
+    }
+}
+
+/// Structs wrapping a collection, used like the collection.
+mod collections {
+    use super::*;
+
+    #[derive(extract_variant, Debug, Clone, PartialEq)]
+    pub enum Value {
+        Number(i64),
+        #[extract_variant(into_iterator)]
+        Array(Vec<Value>),
+    }
+
+    #[test]
+    fn structs_act_as_their_collection() {
+        let array: Array = (1..=3).map(Value::Number).collect();
+        assert_eq!(array.len(), 3);
+        assert_eq!(array[0], Value::Number(1));
+        assert_eq!((&array).into_iter().count(), 3);
+        let values: Vec<Value> = array.into_iter().collect();
+        assert_eq!(values.last(), Some(&Value::Number(3)));
+    }
+}
+
+/// Structs declared by hand, with the conversions still generated.
+mod declared {
+    use super::*;
+    use variant_traits::Variant;
+
+    #[derive(extract_variant)]
+    #[extract_variant(kind)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Shape {
+        Circle(f64),
+        #[exclude(keep_impls)]
+        Rect {
+            width: f64,
+            height: f64,
+        },
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub struct Rect {
+        pub width: f64,
+        pub height: f64,
+    }
+
+    impl Rect {
+        fn area(&self) -> f64 {
+            self.width * self.height
+        }
+    }
+
+    #[test]
+    fn declared_structs_convert() {
+        let rect = Rect {
+            width: 2.0,
+            height: 3.0,
+        };
+        let shape = rect.into_enum();
+        assert_eq!(Rect::try_from(shape).map(|rect| rect.area()), Ok(6.0));
+        assert_eq!(Rect::KIND, ShapeKind::Rect);
+        assert_eq!(Rect::try_from(Shape::Circle(1.0)), Err(Shape::Circle(1.0)));
+    }
+}
+
+mod defaults {
+    use extract_variant::{extract_variant, variant_defaults};
+
+    variant_defaults! {
+        #![extract_variant(kind)]
+        #![prefix(Self)]
+        #![derive(Debug, Clone, PartialEq)]
+    }
+
+    #[derive(extract_variant, Debug, Clone, PartialEq)]
+    #[extract_variant(defaults)]
+    pub enum Request {
+        Get { key: String },
+        Stop,
+    }
+
+    mod nested {
+        use super::*;
+
+        #[derive(extract_variant, Debug, PartialEq)]
+        #[extract_variant(defaults to_getters)]
+        #[prefix(Reply)]
+        pub enum Response {
+            Value(String),
+            Stop,
+        }
+    }
+
+    #[test]
+    fn defaults_apply_to_the_module() {
+        let get = RequestGet {
+            key: "a".to_owned(),
+        };
+        assert_eq!(Request::from(get.clone()).kind(), RequestKind::Get);
+        assert_eq!(RequestGet::try_from(Request::from(get.clone())), Ok(get));
+        // The prefix of the enum overrides the default one
+        let value = nested::Response::Value("b".to_owned());
+        assert_eq!(value.kind(), nested::ResponseKind::Value);
+        assert_eq!(value.to_value(), Some(nested::ReplyValue("b".to_owned())));
+    }
+}
+
+mod cfg_fields {
+    use extract_variant::extract_variant;
+
+    #[derive(extract_variant)]
+    #[extract_variant(kind to_getters exploded)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Handle {
+        File {
+            #[cfg(unix)]
+            fd: i32,
+            #[cfg(not(unix))]
+            handle: usize,
+            path: String,
+        },
+        Closed,
+    }
+
+    #[test]
+    fn configured_out_fields_are_left_out_of_the_conversions() {
+        let file = File {
+            #[cfg(unix)]
+            fd: 3,
+            #[cfg(not(unix))]
+            handle: 3,
+            path: "a".to_owned(),
+        };
+        let handle = Handle::from(file.clone());
+        assert_eq!(handle.to_file(), Some(file.clone()));
+        assert_eq!(File::try_from(handle), Ok(file));
+    }
+}
+
+mod ffi {
+    use extract_variant::extract_variant;
+
+    #[derive(extract_variant)]
+    #[extract_variant(unchecked_getters kind)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C, u8)]
+    pub enum Message {
+        Ping,
+        Data { len: u16, bytes: [u8; 6] } = 4,
+        Wide(u64, u8),
+    }
+
+    #[derive(extract_variant)]
+    #[extract_variant(unchecked_getters)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    pub enum Plain {
+        Small(u8),
+        Large(u64, u32),
+    }
+
+    #[test]
+    fn payloads_are_borrowed_in_place() {
+        let data = Message::Data {
+            len: 2,
+            bytes: [1, 2, 0, 0, 0, 0],
+        };
+        let borrowed = unsafe { data.as_data_unchecked() };
+        assert_eq!(Message::from(*borrowed), data);
+        let wide = Message::Wide(u64::MAX, 7);
+        assert_eq!(unsafe { wide.as_wide_unchecked() }, &Wide(u64::MAX, 7));
+        let large = Plain::Large(1 << 40, 3);
+        assert_eq!(unsafe { large.as_large_unchecked() }, &Large(1 << 40, 3));
+        assert_eq!(unsafe { Plain::Small(9).as_small_unchecked() }.0, 9);
+        assert_eq!(MessageKind::Data as u8, 4);
+    }
+}
+
+/// Fixtures under a cfg of the crate's choosing, which needs no `fixtures` feature declared.
+mod fixtures {
+    use extract_variant::extract_variant;
+
+    #[derive(extract_variant)]
+    #[extract_variant(fixtures(cfg = test) kind)]
+    #[derive(Debug, PartialEq)]
+    pub enum Request {
+        #[sample(path = String::from("/"))]
+        Get {
+            path: String,
+            retries: u8,
+        },
+        Cancel(u32),
+    }
+
+    #[test]
+    fn samples_are_built() {
+        assert_eq!(
+            Get::sample(),
+            Get {
+                path: String::from("/"),
+                retries: 0
+            }
+        );
+        assert_eq!(Request::sample_of(RequestKind::Cancel), Request::Cancel(0));
+    }
+}
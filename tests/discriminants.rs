@@ -0,0 +1,180 @@
+use extract_variant::extract_variant;
+
+const STATUS_BASE: u16 = 400;
+
+#[derive(extract_variant)]
+#[extract_variant(kind exploded)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u16)]
+pub enum Status {
+    BadRequest = STATUS_BASE,
+    NotFound = STATUS_BASE + 4,
+    Teapot = { STATUS_BASE + 18 },
+    Next,
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind)]
+#[derive(Debug, PartialEq)]
+#[repr(u8)]
+pub enum Packet {
+    Ping = 1,
+    Data(Vec<u8>) = 0x10,
+    Close { code: u16 } = 0xff,
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind kind_index kind_ord)]
+#[repr(u8)]
+pub enum Ranked {
+    High = 200,
+    Low(u8) = 1,
+    #[exclude(all)]
+    Hidden,
+    Middle {
+        value: u8,
+    } = 100,
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind)]
+#[repr(u8)]
+pub enum Level {
+    Quiet = 1,
+    #[exclude(all)]
+    Unknown,
+    Moderate,
+    Loud,
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind kind_key compare_by_kind)]
+pub enum Reading {
+    Temperature(f64),
+    Pressure { value: f64 },
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind kind_repr(u16))]
+#[repr(u8)]
+pub enum Stored {
+    Draft(String),
+    #[exclude(all)]
+    Deleted,
+    Published {
+        id: u64,
+    } = 7,
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind wrong_variant handler)]
+pub enum Job {
+    Queued(u32),
+    #[exclude(kind_only)]
+    Cancelled(String),
+}
+
+#[test]
+fn c_like_variants_are_extracted() {
+    let status: Status = NotFound.into();
+    assert_eq!(status, Status::NotFound);
+    assert_eq!(NotFound::try_from(status), Ok(NotFound));
+    assert!(matches!(
+        StatusExploded::from(Status::Teapot),
+        StatusExploded::Teapot(Teapot)
+    ));
+}
+
+#[test]
+fn kinds_keep_discriminants() {
+    assert_eq!(StatusKind::BadRequest as u16, 400);
+    assert_eq!(StatusKind::NotFound as u16, 404);
+    assert_eq!(StatusKind::Teapot as u16, 418);
+    assert_eq!(StatusKind::Next as u16, 419);
+    assert_eq!(Status::Next.kind() as u16, Status::Next as u16);
+}
+
+#[test]
+fn kinds_after_an_excluded_variant_keep_discriminants() {
+    assert_eq!(LevelKind::Quiet as u8, Level::Quiet as u8);
+    assert_eq!(LevelKind::Moderate as u8, Level::Moderate as u8);
+    assert_eq!(LevelKind::Loud as u8, 4);
+    assert_eq!(Level::Unknown.kind(), None);
+}
+
+#[test]
+fn data_variants_with_discriminants_are_extracted() {
+    let packet: Packet = Data(vec![1, 2]).into();
+    assert_eq!(packet.kind() as u8, 0x10);
+    assert_eq!(Data::try_from(packet), Ok(Data(vec![1, 2])));
+    assert_eq!(PacketKind::Close as u8, 0xff);
+    assert_eq!(Close::try_from(Packet::Ping), Err(Packet::Ping));
+}
+
+#[test]
+fn indexed_kinds_follow_declaration_order() {
+    assert_eq!(RankedKind::High.index(), 0);
+    assert_eq!(RankedKind::Middle as usize, 2);
+    assert_eq!(RankedKind::from_index(1), Some(RankedKind::Low));
+    assert_eq!(RankedKind::from_index(3), None);
+    assert!(RankedKind::High < RankedKind::Low);
+    assert_eq!(Ranked::Middle { value: 0 }.kind(), Some(RankedKind::Middle));
+}
+
+#[test]
+fn kind_keys_group_values_in_maps() {
+    let mut counts = std::collections::HashMap::new();
+    for reading in [
+        Reading::Temperature(1.0),
+        Reading::Pressure { value: 2.0 },
+        Reading::Temperature(f64::NAN),
+    ] {
+        *counts.entry(reading.kind_key()).or_insert(0) += 1;
+    }
+    assert_eq!(counts[&ReadingKind::Temperature], 2);
+    assert_eq!(counts[&ReadingKind::Pressure], 1);
+}
+
+#[test]
+fn values_compare_by_kind() {
+    let temperature = Reading::Temperature(1.0);
+    assert!(temperature.same_kind(&Reading::Temperature(f64::NAN)));
+    assert!(!temperature.same_kind(&Reading::Pressure { value: 1.0 }));
+    assert!(temperature == ReadingKind::Temperature);
+    assert!(temperature != ReadingKind::Pressure);
+}
+
+#[test]
+fn kinds_convert_to_and_from_their_repr() {
+    assert_eq!(StoredKind::Published.as_u16(), 7);
+    assert_eq!(u16::from(StoredKind::Draft), 0);
+    assert_eq!(StoredKind::try_from(7), Ok(StoredKind::Published));
+    let error = StoredKind::try_from(1).unwrap_err();
+    assert_eq!(*error.value(), 1);
+    assert_eq!(error.to_string(), "no variant has the discriminant `1`");
+}
+
+#[test]
+fn kind_only_variants_keep_their_kind() {
+    let cancelled = Job::Cancelled("timeout".to_owned());
+    assert_eq!(cancelled.kind(), JobKind::Cancelled);
+    let Err(error) = Queued::try_from(cancelled) else {
+        panic!("expected a `WrongVariant`");
+    };
+    assert_eq!(error.actual_kind(), JobKind::Cancelled);
+    assert_eq!(
+        error.to_string(),
+        "expected variant `Queued`, found `Cancelled`"
+    );
+
+    struct Counter(usize);
+    impl JobHandler for Counter {
+        fn on_queued(&mut self, _: Queued) {
+            self.0 += 1;
+        }
+    }
+    let mut counter = Counter(0);
+    Job::Cancelled(String::new()).dispatch(&mut counter);
+    Job::Queued(1).dispatch(&mut counter);
+    assert_eq!(counter.0, 1);
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "nightly_const")]
+#![feature(const_trait_impl, const_convert)]
+
+use extract_variant::extract_variant;
+
+#[derive(extract_variant)]
+enum Setting {
+    Limit(u32),
+    Named { id: u8 },
+}
+
+const LIMIT: Setting = Setting::from(Limit(10));
+const ID: u8 = match Named::try_from(Setting::Named { id: 3 }) {
+    Ok(named) => named.id,
+    Err(_) => 0,
+};
+
+#[test]
+fn conversions_in_const() {
+    assert!(matches!(LIMIT, Setting::Limit(10)));
+    assert_eq!(ID, 3);
+}
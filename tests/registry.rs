@@ -0,0 +1,92 @@
+use extract_variant::extract_variant;
+
+#[derive(extract_variant)]
+#[extract_variant(kind exploded registry)]
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Quit,
+    Move(i32, i32),
+    #[exclude]
+    Say {
+        text: String,
+    },
+    #[exclude(all)]
+    Internal,
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind exploded registry order(alphabetical))]
+pub enum Sorted {
+    Zulu,
+    Alpha(u8),
+    #[exclude]
+    Mike,
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind exploded registry)]
+#[derive(Debug, PartialEq)]
+pub enum Signal {
+    Start,
+    #[exclude(kind_only)]
+    Reload(u8),
+}
+
+#[test]
+fn variants_are_described() {
+    let described: Vec<_> = COMMAND_VARIANTS
+        .iter()
+        .map(|info| (info.name(), info.kind(), info.field_names()))
+        .collect();
+    assert_eq!(
+        described,
+        [
+            ("Quit", CommandKind::Quit, &[][..]),
+            ("Move", CommandKind::Move, &["0", "1"]),
+            ("Say", CommandKind::Say, &["text"]),
+        ]
+    );
+}
+
+#[test]
+fn variants_keeping_their_kind_are_listed() {
+    let [start, reload] = SIGNAL_VARIANTS else {
+        panic!("unexpected variants");
+    };
+    assert_eq!(start.kind(), SignalKind::Start);
+    assert_eq!(reload.kind(), SignalKind::Reload);
+    assert_eq!(
+        reload.construct(SignalExploded::Reload(3)),
+        Some(Signal::Reload(3))
+    );
+}
+
+#[test]
+fn variants_are_built_from_the_exploded_enum() {
+    let [quit, moved, say] = COMMAND_VARIANTS else {
+        panic!("unexpected variants");
+    };
+    assert_eq!(
+        moved.construct(CommandExploded::Move(Move(1, 2))),
+        Some(Command::Move(1, 2))
+    );
+    assert_eq!(quit.construct(CommandExploded::Move(Move(1, 2))), None);
+    let text = "hi".to_owned();
+    assert_eq!(
+        say.construct(CommandExploded::Say { text: text.clone() }),
+        Some(Command::Say { text })
+    );
+}
+
+#[test]
+fn alphabetical_order_sorts_the_lists() {
+    let names: Vec<_> = SORTED_VARIANTS.iter().map(|info| info.name()).collect();
+    assert_eq!(names, ["Alpha", "Mike", "Zulu"]);
+    let _: <Sorted as variant_traits::Variants>::List = (Alpha(0), Zulu);
+    assert!(matches!(
+        SortedExploded::from(Sorted::Mike),
+        SortedExploded::Mike
+    ));
+    // The kind enum keeps mirroring the enum
+    assert_eq!(SortedKind::Zulu as u8, 0);
+}
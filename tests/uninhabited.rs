@@ -0,0 +1,57 @@
+// `Single` can't hold a value at all, which is exactly what this lint points out
+#![allow(clippy::infallible_try_from)]
+
+use std::convert::Infallible;
+
+use extract_variant::extract_variant;
+
+#[derive(extract_variant)]
+#[extract_variant(kind exploded handler to_getters hygienic)]
+pub enum Never {}
+
+#[derive(extract_variant)]
+#[extract_variant(kind exploded handler to_getters)]
+#[derive(Clone, Debug)]
+pub enum Single {
+    Only(Infallible),
+}
+
+#[derive(extract_variant)]
+#[extract_variant(kind exploded handler to_getters)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mixed {
+    Impossible(Infallible),
+    HalfImpossible { never: Infallible, count: u8 },
+    Possible(u8),
+}
+
+#[test]
+fn uninhabited_variants_are_never_extracted() {
+    let mixed = Mixed::Possible(1);
+    assert!(Impossible::try_from(mixed.clone()).is_err());
+    assert!(HalfImpossible::try_from(mixed.clone()).is_err());
+    assert_eq!(mixed.to_impossible().map(|_| ()), None);
+    assert_eq!(mixed.kind(), MixedKind::Possible);
+}
+
+#[test]
+fn inhabited_variants_still_convert() {
+    let mixed: Mixed = Possible(1).into();
+    assert_eq!(mixed, Mixed::Possible(1));
+    assert_eq!(Possible::try_from(mixed).ok(), Some(Possible(1)));
+    assert!(matches!(
+        MixedExploded::from(Mixed::Possible(2)),
+        MixedExploded::Possible(Possible(2))
+    ));
+}
+
+#[test]
+fn empty_enum_functions_are_callable() {
+    fn _absurd(never: Never) -> Single {
+        match never {}
+    }
+    fn _only(single: Single) -> Infallible {
+        let Single::Only(never) = single;
+        never
+    }
+}
@@ -1 +1,405 @@
-pub trait Variant<Enum>: Into<Enum> + TryFrom<Enum, Error = Enum> {}
+use std::marker::PhantomData;
+
+pub trait Variant<Enum>: Into<Enum> + TryFrom<Enum, Error = Enum> {
+    /// Wraps the variant into its enum, the same as [Into::into].
+    fn into_enum(self) -> Enum {
+        self.into()
+    }
+
+    /// Unwraps the variant from the enum, or gives the enum back if it holds another variant. The
+    /// same as [TryFrom::try_from].
+    fn from_enum(value: Enum) -> Result<Self, Enum> {
+        Self::try_from(value)
+    }
+}
+
+/// Converts a variant of `E` into another one, by wrapping it into the enum and unwrapping it as
+/// `B`. Gives the enum back if `a` isn't wrapped into the variant `B` stands for.
+pub fn convert<A: Variant<E>, B: Variant<E>, E>(a: A) -> Result<B, E> {
+    B::from_enum(a.into_enum())
+}
+
+/// The other side of [Variant], implemented by the enum for each of its variants: generic code can
+/// then be bounded on the enum ("any enum that can hold a `Timeout`") instead of on the variant.
+///
+/// An enum implements it for each of its [Variant]s through a blanket impl, so that both sides
+/// are always in sync. Implement it by hand only for a type that can't be a [Variant], e.g. because
+/// its conversion from the enum fails with another error.
+pub trait HasVariant<V>: Sized {
+    /// Wraps `variant` into the enum.
+    fn insert(variant: V) -> Self;
+
+    /// Unwraps the variant from `self`, or gives `self` back if it holds another variant.
+    fn extract(self) -> Result<V, Self>;
+}
+
+impl<E, V: Variant<E>> HasVariant<V> for E {
+    fn insert(variant: V) -> Self {
+        variant.into()
+    }
+
+    fn extract(self) -> Result<V, Self> {
+        V::try_from(self)
+    }
+}
+
+/// Implemented by an enum to list the types of its extracted variants, for metaprogramming over
+/// them.
+pub trait Variants {
+    /// The types of the extracted variants as a tuple, in declaration order unless the enum
+    /// chooses another one with the `order` option of `extract_variant`.
+    type List;
+}
+
+/// The error of parsing a kind enum generated by `extract_variant` from a string that isn't the
+/// name of any of its variants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKindError {
+    input: String,
+}
+
+impl ParseKindError {
+    /// Creates the error for the given unknown name.
+    pub fn new(input: &str) -> Self {
+        Self {
+            input: input.to_owned(),
+        }
+    }
+
+    /// The string that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl std::fmt::Display for ParseKindError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown variant `{}`", self.input)
+    }
+}
+
+impl std::error::Error for ParseKindError {}
+
+/// The error of converting an integer into a kind enum generated by `extract_variant` with
+/// `kind_repr`, when it isn't the discriminant of any of its variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidKindError<T> {
+    value: T,
+}
+
+impl<T> InvalidKindError<T> {
+    /// Creates the error for the given unknown discriminant.
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// The integer that failed to convert.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for InvalidKindError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no variant has the discriminant `{}`", self.value)
+    }
+}
+
+impl<T: std::fmt::Debug + std::fmt::Display> std::error::Error for InvalidKindError<T> {}
+
+/// Implemented by an enum with a kind enum generated by `extract_variant`, listing its variants
+/// without their fields.
+pub trait HasKind {
+    /// The kind enum.
+    type Kind;
+
+    /// Returns the kind of the variant `self` holds.
+    fn kind(&self) -> Self::Kind;
+}
+
+/// The error of unwrapping a variant from an enum that holds another one, generated instead of
+/// the enum itself by the `wrong_variant` option of `extract_variant`. It gives the enum back, and
+/// tells which variant was expected and which kind was found.
+#[derive(Clone, PartialEq, Eq)]
+pub struct WrongVariant<E> {
+    expected: &'static str,
+    value: E,
+}
+
+impl<E> WrongVariant<E> {
+    /// Creates the error of expecting the variant named `expected` in `value`.
+    pub const fn new(expected: &'static str, value: E) -> Self {
+        Self { expected, value }
+    }
+
+    /// The name of the variant that was expected.
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    /// The kind of the variant the enum actually holds.
+    pub fn actual_kind(&self) -> E::Kind
+    where
+        E: HasKind,
+    {
+        self.value.kind()
+    }
+
+    /// Gives the enum back.
+    pub fn into_inner(self) -> E {
+        self.value
+    }
+}
+
+impl<E: HasKind> std::fmt::Debug for WrongVariant<E>
+where
+    E::Kind: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WrongVariant")
+            .field("expected", &self.expected)
+            .field("actual", &self.actual_kind())
+            .finish()
+    }
+}
+
+impl<E: HasKind> std::fmt::Display for WrongVariant<E>
+where
+    E::Kind: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected variant `{}`, found `{:?}`",
+            self.expected,
+            self.actual_kind()
+        )
+    }
+}
+
+impl<E: HasKind> std::error::Error for WrongVariant<E> where E::Kind: std::fmt::Debug {}
+
+/// What is known of a variant of `E` at runtime, listed by the `registry` option of
+/// `extract_variant` for every variant, along with its kind `K` and a constructor taking the
+/// exploded enum `X`.
+pub struct VariantInfo<E, K, X> {
+    name: &'static str,
+    kind: K,
+    field_names: &'static [&'static str],
+    construct: fn(X) -> Option<E>,
+}
+
+impl<E, K: Copy, X> VariantInfo<E, K, X> {
+    /// Describes the variant named `name`, whose `construct` function builds the enum from the
+    /// exploded enum if it holds this variant.
+    pub const fn new(
+        name: &'static str,
+        kind: K,
+        field_names: &'static [&'static str],
+        construct: fn(X) -> Option<E>,
+    ) -> Self {
+        Self {
+            name,
+            kind,
+            field_names,
+            construct,
+        }
+    }
+
+    /// The name of the variant.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The kind of the variant.
+    pub fn kind(&self) -> K {
+        self.kind
+    }
+
+    /// The names of the fields of the variant, in declaration order, which are their positions in
+    /// tuple variants.
+    pub fn field_names(&self) -> &'static [&'static str] {
+        self.field_names
+    }
+
+    /// Builds the enum from `exploded`, or returns `None` if it holds another variant.
+    pub fn construct(&self, exploded: X) -> Option<E> {
+        (self.construct)(exploded)
+    }
+}
+
+impl<E, K: std::fmt::Debug, X> std::fmt::Debug for VariantInfo<E, K, X> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VariantInfo")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("field_names", &self.field_names)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Methods available on any enum, for its types implementing [Variant].
+pub trait EnumExt: Sized {
+    /// If `self` currently holds the variant `V`, takes it out, maps it with `f`, and stores the
+    /// result back in place. Returns whether `self` held `V`.
+    ///
+    /// `self` is moved out for the duration of the call, so a panic in `f` (or in the conversions)
+    /// aborts the process instead of unwinding.
+    ///
+    /// ```
+    /// use variant_traits::{EnumExt, Variant};
+    ///
+    /// enum Shape {
+    ///     Circle(f64),
+    ///     Point,
+    /// }
+    /// struct Circle(f64);
+    /// impl From<Circle> for Shape {
+    ///     fn from(Circle(r): Circle) -> Self {
+    ///         Shape::Circle(r)
+    ///     }
+    /// }
+    /// impl TryFrom<Shape> for Circle {
+    ///     type Error = Shape;
+    ///     fn try_from(value: Shape) -> Result<Self, Shape> {
+    ///         if let Shape::Circle(r) = value { Ok(Circle(r)) } else { Err(value) }
+    ///     }
+    /// }
+    /// impl Variant<Shape> for Circle {}
+    ///
+    /// let mut shape = Shape::Circle(1.0);
+    /// assert!(shape.map_variant(|Circle(r)| Circle(r * 2.0)));
+    /// assert!(matches!(shape, Shape::Circle(r) if r == 2.0));
+    /// assert!(!Shape::Point.map_variant(|Circle(r)| Circle(r * 2.0)));
+    /// ```
+    fn map_variant<V: Variant<Self>>(&mut self, f: impl FnOnce(V) -> V) -> bool {
+        struct AbortOnUnwind;
+        impl Drop for AbortOnUnwind {
+            fn drop(&mut self) {
+                std::process::abort();
+            }
+        }
+
+        let guard = AbortOnUnwind;
+        // SAFETY: `self` is written back below before anything can observe it, and unwinding
+        // in between aborts the process, so the value is never dropped or read twice.
+        let value = unsafe { std::ptr::read(self) };
+        let (value, held) = match V::from_enum(value) {
+            Ok(variant) => (f(variant).into_enum(), true),
+            Err(value) => (value, false),
+        };
+        // SAFETY: the old value was moved out by `ptr::read` above, so overwriting it without
+        // dropping it is what keeps it from being dropped twice, and the guard has aborted on any
+        // unwind that could have left `self` observed before this write.
+        unsafe { std::ptr::write(self, value) };
+        std::mem::forget(guard);
+        held
+    }
+}
+
+impl<E> EnumExt for E {}
+
+/// Methods available on any iterator, to go between variants and their enum in bulk.
+pub trait VariantIterExt: Iterator + Sized {
+    /// Wraps each variant into its enum, and collects the enums, e.g. to turn a `Vec<Circle>` into
+    /// a `Vec<Shape>`.
+    ///
+    /// ```
+    /// use variant_traits::{Variant, VariantIterExt};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Shape {
+    ///     Circle(f64),
+    ///     Point,
+    /// }
+    /// struct Circle(f64);
+    /// impl From<Circle> for Shape {
+    ///     fn from(Circle(r): Circle) -> Self {
+    ///         Shape::Circle(r)
+    ///     }
+    /// }
+    /// impl TryFrom<Shape> for Circle {
+    ///     type Error = Shape;
+    ///     fn try_from(value: Shape) -> Result<Self, Shape> {
+    ///         if let Shape::Circle(r) = value { Ok(Circle(r)) } else { Err(value) }
+    ///     }
+    /// }
+    /// impl Variant<Shape> for Circle {}
+    ///
+    /// let shapes: Vec<Shape> = vec![Circle(1.0), Circle(2.0)].into_iter().collect_into_enum();
+    /// assert_eq!(shapes, [Shape::Circle(1.0), Shape::Circle(2.0)]);
+    /// ```
+    fn collect_into_enum<E, C>(self) -> C
+    where
+        Self::Item: Variant<E>,
+        C: FromIterator<E>,
+    {
+        self.map(Variant::into_enum).collect()
+    }
+
+    /// Only yields the enums holding the variant `V`, unwrapped, e.g. to pick the events of one
+    /// type out of a stream of them.
+    ///
+    /// ```
+    /// use variant_traits::{HasVariant, VariantIterExt};
+    ///
+    /// enum Event {
+    ///     Click(u32),
+    ///     Close,
+    /// }
+    /// struct Click(u32);
+    /// impl HasVariant<Click> for Event {
+    ///     fn insert(Click(n): Click) -> Self {
+    ///         Event::Click(n)
+    ///     }
+    ///     fn extract(self) -> Result<Click, Self> {
+    ///         if let Event::Click(n) = self { Ok(Click(n)) } else { Err(self) }
+    ///     }
+    /// }
+    ///
+    /// let events = vec![Event::Click(1), Event::Close, Event::Click(2)];
+    /// let clicks: Vec<u32> = events.into_iter().filter_variants::<Click>().map(|c| c.0).collect();
+    /// assert_eq!(clicks, [1, 2]);
+    /// ```
+    fn filter_variants<V>(self) -> FilterVariants<Self, V>
+    where
+        Self::Item: HasVariant<V>,
+    {
+        FilterVariants {
+            iter: self,
+            variant: PhantomData,
+        }
+    }
+}
+
+impl<I: Iterator> VariantIterExt for I {}
+
+/// The iterator returned by [VariantIterExt::filter_variants].
+pub struct FilterVariants<I, V> {
+    iter: I,
+    variant: PhantomData<fn() -> V>,
+}
+
+impl<I, V> Iterator for FilterVariants<I, V>
+where
+    I: Iterator,
+    I::Item: HasVariant<V>,
+{
+    type Item = V;
+
+    fn next(&mut self) -> Option<V> {
+        self.iter.find_map(|value| value.extract().ok())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I: std::fmt::Debug, V> std::fmt::Debug for FilterVariants<I, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilterVariants")
+            .field("iter", &self.iter)
+            .finish()
+    }
+}